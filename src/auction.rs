@@ -0,0 +1,210 @@
+use std::fmt;
+
+use contract::{Contract, ContractDoubled, ContractNumber, ContractSuit, Seat};
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Call {
+    Bid(ContractNumber, ContractSuit),
+    Pass,
+    Double,
+    Redouble,
+}
+
+impl fmt::Display for Call {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Call::Bid(number, suit) => write!(f, "{}{}", number, suit),
+            Call::Pass              => write!(f, "P"),
+            Call::Double            => write!(f, "X"),
+            Call::Redouble          => write!(f, "XX"),
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum AuctionError {
+    Finished,
+    // Not higher than the last bid.
+    InsufficientBid,
+    // Only the opponents' undoubled bid can be doubled.
+    CannotDouble,
+    // Only a double by the opponents can be redoubled.
+    CannotRedouble,
+}
+
+// The calls made on one board, starting with the dealer. Only legal calls
+// are ever added.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Auction {
+    dealer_ : Seat,
+    calls_ : Vec<Call>,
+}
+
+impl Auction {
+    pub fn new(dealer : Seat) -> Auction {
+        Auction {
+            dealer_ : dealer,
+            calls_ : Vec::new(),
+        }
+    }
+
+    pub fn dealer(&self) -> Seat {
+        self.dealer_
+    }
+
+    pub fn calls(&self) -> &[Call] {
+        &self.calls_
+    }
+
+    // Every call with the seat that made it.
+    pub fn seated_calls(&self) -> Vec<(Seat, Call)> {
+        let mut seat = self.dealer_;
+        self.calls_.iter().map(|&call| {
+            let made = (seat, call);
+            seat = seat.next();
+            made
+        }).collect()
+    }
+
+    // The seat whose turn it is to call.
+    pub fn next_seat(&self) -> Seat {
+        (0..self.calls_.len()).fold(self.dealer_, |s, _| s.next())
+    }
+
+    // Three passes in a row end the auction, or four if nobody has bid.
+    pub fn is_finished(&self) -> bool {
+        let n = self.calls_.len();
+        n >= 4 && self.calls_[n - 3..].iter().all(|&c| c == Call::Pass)
+    }
+
+    pub fn is_passed_out(&self) -> bool {
+        self.is_finished() && self.last_bid().is_none()
+    }
+
+    // The last bid and who made it.
+    fn last_bid(&self) -> Option<(Seat, ContractNumber, ContractSuit)> {
+        self.seated_calls().into_iter().rev().filter_map(|(seat, call)| {
+            match call {
+                Call::Bid(number, suit) => Some((seat, number, suit)),
+                _                       => None,
+            }
+        }).next()
+    }
+
+    // The last call other than a pass, and who made it.
+    fn last_action(&self) -> Option<(Seat, Call)> {
+        self.seated_calls().into_iter().rev()
+            .find(|&(_, call)| call != Call::Pass)
+    }
+
+    pub fn check(&self, call : Call) -> Result<(), AuctionError> {
+        if self.is_finished() {
+            return Err(AuctionError::Finished);
+        }
+        let side = self.next_seat().side();
+        match (call, self.last_action()) {
+            (Call::Pass, _) => Ok(()),
+            (Call::Bid(number, suit), _) => match self.last_bid() {
+                Some((_, n, s)) if (number, suit) <= (n, s) =>
+                    Err(AuctionError::InsufficientBid),
+                _ => Ok(()),
+            },
+            (Call::Double, Some((seat, Call::Bid(..))))
+                if seat.side() != side => Ok(()),
+            (Call::Double, _) => Err(AuctionError::CannotDouble),
+            (Call::Redouble, Some((seat, Call::Double)))
+                if seat.side() != side => Ok(()),
+            (Call::Redouble, _) => Err(AuctionError::CannotRedouble),
+        }
+    }
+
+    pub fn call(&mut self, call : Call) -> Result<(), AuctionError> {
+        self.check(call)?;
+        self.calls_.push(call);
+        Ok(())
+    }
+
+    // Takes back the last call.
+    pub fn undo(&mut self) -> Option<Call> {
+        self.calls_.pop()
+    }
+
+    // The contract as it stands, even if the auction isn't over yet.
+    pub fn contract(&self) -> Option<Contract> {
+        let (_, number, suit) = self.last_bid()?;
+        let doubled = match self.last_action() {
+            Some((_, Call::Double))   => ContractDoubled::Doubled,
+            Some((_, Call::Redouble)) => ContractDoubled::Redoubled,
+            _                         => ContractDoubled::Undoubled,
+        };
+        Some(Contract::new(suit, number, doubled))
+    }
+
+    // The first player on the side that won the auction to name its strain.
+    pub fn declarer(&self) -> Option<Seat> {
+        let (bidder, _, strain) = self.last_bid()?;
+        self.seated_calls().into_iter().find(|&(seat, call)| match call {
+            Call::Bid(_, suit) => suit == strain &&
+                seat.side() == bidder.side(),
+            _ => false,
+        }).map(|c| c.0)
+    }
+
+    // The final contract and declarer, once the auction is over.
+    pub fn result(&self) -> Option<(Contract, Seat)> {
+        if !self.is_finished() {
+            return None;
+        }
+        Some((self.contract()?, self.declarer()?))
+    }
+}
+
+#[cfg(test)]
+fn auction(dealer : Seat, calls : &[&str]) -> Result<Auction, AuctionError> {
+    let mut auction = Auction::new(dealer);
+    for call in calls {
+        auction.call(call.parse().unwrap())?;
+    }
+    Ok(auction)
+}
+
+#[test]
+fn test_declarer() {
+    let a = auction(Seat::East, &["1H", "P", "1S", "P", "2S", "P", "4S", "X",
+                                  "P", "P", "P"]).unwrap();
+    assert!(a.is_finished() && !a.is_passed_out());
+    assert!(a.contract().unwrap().to_string() == "4SX");
+    // West bid spades first, though East bid them last.
+    assert!(a.result().unwrap().1 == Seat::West);
+
+    let a = auction(Seat::North, &["1NT", "X", "XX", "P", "P", "P"]).unwrap();
+    assert!(a.result().unwrap().0.to_string() == "1NTXX");
+    assert!(a.next_seat() == Seat::South);
+
+    let a = auction(Seat::South, &["P", "P", "P"]).unwrap();
+    assert!(!a.is_finished() && a.result().is_none());
+    let a = auction(Seat::South, &["P", "P", "P", "P"]).unwrap();
+    assert!(a.is_passed_out() && a.result().is_none());
+}
+
+#[test]
+fn test_illegal_calls() {
+    assert!(auction(Seat::North, &["1S", "1H"]) ==
+            Err(AuctionError::InsufficientBid));
+    assert!(auction(Seat::North, &["1S", "1S"]) ==
+            Err(AuctionError::InsufficientBid));
+    assert!(auction(Seat::North, &["1S", "1NT", "2C"]).is_ok());
+    assert!(auction(Seat::North, &["X"]) == Err(AuctionError::CannotDouble));
+    // Partner's bid, even with a pass in between.
+    assert!(auction(Seat::North, &["1S", "P", "X"]) ==
+            Err(AuctionError::CannotDouble));
+    assert!(auction(Seat::North, &["1S", "X", "X"]) ==
+            Err(AuctionError::CannotDouble));
+    assert!(auction(Seat::North, &["1S", "X", "P", "P", "XX"]).is_ok());
+    assert!(auction(Seat::North, &["1S", "P", "P", "XX"]) ==
+            Err(AuctionError::CannotRedouble));
+    assert!(auction(Seat::North, &["1S", "X", "P", "XX"]) ==
+            Err(AuctionError::CannotRedouble));
+    assert!(auction(Seat::North, &["1S", "P", "P", "P", "P"]) ==
+            Err(AuctionError::Finished));
+}
@@ -0,0 +1,36 @@
+use contract::{Seat, SEATS};
+
+// Duplicate boards have the dealer and vulnerability marked on them, in a
+// cycle that starts again every sixteen boards.
+pub const BOARDS_PER_CYCLE : u32 = 16;
+
+// NS and EW vulnerability of boards 1 to 16.
+const VULNERABILITY : [(bool, bool); 16] = [
+    (false, false), (true,  false), (false, true),  (true,  true),
+    (true,  false), (false, true),  (true,  true),  (false, false),
+    (false, true),  (true,  true),  (false, false), (true,  false),
+    (true,  true),  (false, false), (true,  false), (false, true),
+];
+
+// The deal passes clockwise, starting with North on board 1.
+pub fn dealer(board_num : u32) -> Seat {
+    SEATS[((board_num - 1) % 4) as usize]
+}
+
+pub fn vulnerability(board_num : u32) -> (bool, bool) {
+    VULNERABILITY[((board_num - 1) % BOARDS_PER_CYCLE) as usize]
+}
+
+#[test]
+fn test_cycle() {
+    assert!(dealer(1) == Seat::North && dealer(8) == Seat::West);
+    assert!(vulnerability(1) == (false, false));
+    assert!(vulnerability(5) == (true, false));
+    assert!(vulnerability(9) == (false, true));
+    assert!(vulnerability(13) == (true, true));
+    assert!(vulnerability(16) == (false, true));
+    for board in 1..=BOARDS_PER_CYCLE {
+        assert!(vulnerability(board + 16) == vulnerability(board));
+        assert!(dealer(board + 32) == dealer(board));
+    }
+}
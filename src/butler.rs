@@ -0,0 +1,184 @@
+use std::collections::BTreeMap;
+
+use imp::imps;
+use session::{PairId, Session, TableResult};
+
+// IMP scoring for pairs events, either against a datum (Butler) or against
+// every other result on the board (cross-IMPs).
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Method {
+    // Drop this many of the top and the bottom scores before averaging.
+    Butler { trim : usize },
+    // Divide by the number of comparisons to stay on the Butler scale.
+    CrossImps { averaged : bool },
+}
+
+pub struct ImpRow {
+    pub ns_pair : PairId,
+    pub ew_pair : PairId,
+    pub ns_score : i32,
+    pub ns_imps : f64,
+    pub ew_imps : f64,
+}
+
+pub struct ImpBoard {
+    pub board_num : u32,
+    // Only for Butler scoring.
+    pub datum : Option<i32>,
+    pub rows : Vec<ImpRow>,
+}
+
+pub struct PairImps {
+    pub pair : PairId,
+    pub imps : f64,
+    pub boards : u32,
+}
+
+impl PairImps {
+    pub fn average(&self) -> f64 {
+        if self.boards == 0 { 0.0 } else { self.imps / self.boards as f64 }
+    }
+}
+
+// The average NS score without the `trim` highest and lowest, rounded to
+// the nearest 10. Nothing is trimmed if that would leave no scores.
+pub fn datum(scores : &[i32], trim : usize) -> i32 {
+    let mut sorted = scores.to_vec();
+    sorted.sort();
+    let kept = if sorted.len() > 2 * trim {
+        &sorted[trim..sorted.len() - trim]
+    } else {
+        &sorted[..]
+    };
+    if kept.is_empty() {
+        return 0;
+    }
+    let average = kept.iter().sum::<i32>() as f64 / kept.len() as f64;
+    ((average / 10.0).round() * 10.0) as i32
+}
+
+fn imp_row(result : &TableResult, ns_score : i32, ns_imps : f64) -> ImpRow {
+    ImpRow {
+        ns_pair : result.ns_pair,
+        ew_pair : result.ew_pair,
+        ns_score,
+        ns_imps,
+        ew_imps : -ns_imps,
+    }
+}
+
+pub fn score_boards(session : &Session, method : Method) -> Vec<ImpBoard> {
+    session.by_board().into_iter().map(|(board_num, results)| {
+        let scores : Vec<i32> = results.iter().map(|r| r.1).collect();
+        let (datum, rows) = match method {
+            Method::Butler { trim } => {
+                let datum = datum(&scores, trim);
+                let rows = results.iter().map(|&(result, score)| {
+                    imp_row(result, score, imps(score - datum) as f64)
+                }).collect();
+                (Some(datum), rows)
+            },
+            Method::CrossImps { averaged } => {
+                let rows = results.iter().enumerate()
+                    .map(|(ix, &(result, score))| {
+                        let total : i32 = scores.iter().enumerate()
+                            .filter(|&(other, _)| other != ix)
+                            .map(|(_, &other)| imps(score - other))
+                            .sum();
+                        let ns_imps = if averaged && scores.len() > 1 {
+                            total as f64 / (scores.len() - 1) as f64
+                        } else {
+                            total as f64
+                        };
+                        imp_row(result, score, ns_imps)
+                    }).collect();
+                (None, rows)
+            },
+        };
+        ImpBoard {
+            board_num,
+            datum,
+            rows,
+        }
+    }).collect()
+}
+
+// Totals per pair over all boards, best first.
+pub fn pair_totals(boards : &[ImpBoard]) -> Vec<PairImps> {
+    let mut totals : BTreeMap<PairId, (f64, u32)> = BTreeMap::new();
+    for board in boards {
+        for row in &board.rows {
+            for &(pair, imps) in &[(row.ns_pair, row.ns_imps),
+                                   (row.ew_pair, row.ew_imps)] {
+                let total = totals.entry(pair).or_insert((0.0, 0));
+                total.0 += imps;
+                total.1 += 1;
+            }
+        }
+    }
+    let mut pairs : Vec<PairImps> =
+        totals.into_iter().map(|(pair, (imps, boards))| PairImps {
+            pair,
+            imps,
+            boards,
+        }).collect();
+    pairs.sort_by(|a, b| b.imps.partial_cmp(&a.imps)
+                  .unwrap().then(a.pair.cmp(&b.pair)));
+    pairs
+}
+
+#[cfg(test)]
+use contract::Seat;
+#[cfg(test)]
+use data::{Entry, Table};
+#[cfg(test)]
+use parse::parse_contract;
+
+#[cfg(test)]
+fn test_session() -> Session {
+    let mut session = Session::new();
+    for &(ns, ew, contract, margin) in &[(1, 11, "4S", 0),
+                                         (2, 12, "4S", 1),
+                                         (3, 13, "2S", 2),
+                                         (4, 14, "4S", -1),
+                                         (5, 15, "6S", 0)] {
+        let mut entry = Entry::new(&Table::new(), Seat::North, 1);
+        entry.set_contract(parse_contract(contract).unwrap());
+        entry.record(margin);
+        session.add(ns, ew, entry);
+    }
+    session
+}
+
+#[test]
+fn test_datum() {
+    assert!(datum(&[420, 450, 170, -50, 980], 0) == 390);
+    assert!(datum(&[420, 450, 170, -50, 980], 1) == 350);
+    assert!(datum(&[420, -50], 1) == 190);
+}
+
+#[test]
+fn test_butler() {
+    let boards = score_boards(&test_session(), Method::Butler { trim : 1 });
+    assert!(boards[0].datum == Some(350));
+    let rows = &boards[0].rows;
+    assert!(rows[0].ns_imps == 2.0);
+    assert!(rows[3].ns_imps == -9.0 && rows[3].ew_imps == 9.0);
+    assert!(rows[4].ns_imps == 12.0);
+    let pairs = pair_totals(&boards);
+    assert!(pairs[0].pair == 5 && pairs[0].average() == 12.0);
+    assert!(pairs.last().unwrap().pair == 15);
+}
+
+#[test]
+fn test_cross_imps() {
+    let session = test_session();
+    let boards = score_boards(&session,
+                              Method::CrossImps { averaged : false });
+    // 420 against 450, 170, -50 and 980.
+    assert!(boards[0].rows[0].ns_imps == (-1 + 6 + 10 - 11) as f64);
+    let boards = score_boards(&session,
+                              Method::CrossImps { averaged : true });
+    assert!(boards[0].rows[0].ns_imps == 1.0);
+    assert!(boards[0].datum.is_none());
+}
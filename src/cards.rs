@@ -0,0 +1,362 @@
+use std::fmt;
+
+use contract::{ContractSuit, Seat, SEATS};
+
+// In rank order, lowest first.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Suit {
+    Clubs, Diamonds, Hearts, Spades
+}
+
+pub const SUITS : [Suit; 4] =
+    [Suit::Clubs, Suit::Diamonds, Suit::Hearts, Suit::Spades];
+
+impl Suit {
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    pub fn from_char(c : char) -> Option<Suit> {
+        match c.to_ascii_uppercase() {
+            'C' => Some(Suit::Clubs),
+            'D' => Some(Suit::Diamonds),
+            'H' => Some(Suit::Hearts),
+            'S' => Some(Suit::Spades),
+            _   => None,
+        }
+    }
+
+    // The trump suit for a strain, None at notrump.
+    pub fn from_strain(strain : ContractSuit) -> Option<Suit> {
+        match strain {
+            ContractSuit::Clubs    => Some(Suit::Clubs),
+            ContractSuit::Diamonds => Some(Suit::Diamonds),
+            ContractSuit::Hearts   => Some(Suit::Hearts),
+            ContractSuit::Spades   => Some(Suit::Spades),
+            ContractSuit::NoTrump  => None,
+        }
+    }
+}
+
+impl fmt::Display for Suit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}",
+               match *self {
+                   Suit::Clubs    => "C",
+                   Suit::Diamonds => "D",
+                   Suit::Hearts   => "H",
+                   Suit::Spades   => "S",
+               })
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Rank {
+    Two, Three, Four, Five, Six, Seven, Eight, Nine, Ten,
+    Jack, Queen, King, Ace
+}
+
+pub const RANKS : [Rank; 13] =
+    [Rank::Two, Rank::Three, Rank::Four, Rank::Five, Rank::Six, Rank::Seven,
+     Rank::Eight, Rank::Nine, Rank::Ten, Rank::Jack, Rank::Queen, Rank::King,
+     Rank::Ace];
+
+const RANK_CHARS : &str = "23456789TJQKA";
+
+impl Rank {
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    pub fn from_char(c : char) -> Option<Rank> {
+        RANK_CHARS.find(c.to_ascii_uppercase()).map(|i| RANKS[i])
+    }
+}
+
+impl fmt::Display for Rank {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", &RANK_CHARS[self.index()..self.index() + 1])
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct Card {
+    pub suit : Suit,
+    pub rank : Rank,
+}
+
+impl Card {
+    pub fn new(suit : Suit, rank : Rank) -> Card {
+        Card {
+            suit,
+            rank,
+        }
+    }
+
+    // 0 to 51, clubs first.
+    pub fn index(self) -> usize {
+        self.suit.index() * 13 + self.rank.index()
+    }
+
+    pub fn from_index(ix : usize) -> Card {
+        Card::new(SUITS[ix / 13], RANKS[ix % 13])
+    }
+}
+
+// Suit then rank, like "SA" or "H7".
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.suit, self.rank)
+    }
+}
+
+// A set of cards, one bit per card.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub struct Hand {
+    cards_ : u64,
+}
+
+impl Hand {
+    pub fn new() -> Hand {
+        Hand {
+            cards_ : 0,
+        }
+    }
+
+    // False if the card was already there.
+    pub fn insert(&mut self, card : Card) -> bool {
+        let had = self.contains(card);
+        self.cards_ |= 1 << card.index();
+        !had
+    }
+
+    pub fn remove(&mut self, card : Card) -> bool {
+        let had = self.contains(card);
+        self.cards_ &= !(1 << card.index());
+        had
+    }
+
+    pub fn contains(&self, card : Card) -> bool {
+        self.cards_ & (1 << card.index()) != 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.cards_.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cards_ == 0
+    }
+
+    // The ranks held in `suit`, highest first.
+    pub fn suit(&self, suit : Suit) -> Vec<Rank> {
+        RANKS.iter().rev().cloned()
+            .filter(|&r| self.contains(Card::new(suit, r)))
+            .collect()
+    }
+
+    pub fn length(&self, suit : Suit) -> usize {
+        ((self.cards_ >> (suit.index() * 13)) & 0x1fff).count_ones() as usize
+    }
+
+    // Spades first, each suit highest first.
+    pub fn cards(&self) -> Vec<Card> {
+        SUITS.iter().rev()
+            .flat_map(|&s| {
+                self.suit(s).into_iter().map(move |r| Card::new(s, r))
+            })
+            .collect()
+    }
+
+    // Milton Work points: 4 for an ace down to 1 for a jack.
+    pub fn hcp(&self) -> u32 {
+        self.cards().iter().map(|c| match c.rank {
+            Rank::Ace   => 4,
+            Rank::King  => 3,
+            Rank::Queen => 2,
+            Rank::Jack  => 1,
+            _           => 0,
+        }).sum()
+    }
+
+    // 4-3-3-3, 4-4-3-2 or 5-3-3-2.
+    pub fn is_balanced(&self) -> bool {
+        let mut lengths : Vec<usize> =
+            SUITS.iter().map(|&s| self.length(s)).collect();
+        lengths.sort();
+        lengths[0] >= 2 && lengths[1] >= 3
+    }
+
+    fn holding(&self, suit : Suit) -> String {
+        self.suit(suit).iter().map(|r| r.to_string()).collect()
+    }
+}
+
+// PBN style: the four suits from spades down, separated by dots.
+impl fmt::Display for Hand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let suits : Vec<String> =
+            SUITS.iter().rev().map(|&s| self.holding(s)).collect();
+        write!(f, "{}", suits.join("."))
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum DealError {
+    WrongCount(Seat, usize),
+    // Held by more than one player.
+    Duplicate(Card),
+}
+
+impl fmt::Display for DealError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DealError::WrongCount(seat, n) =>
+                write!(f, "{} has {} cards", seat, n),
+            DealError::Duplicate(card) =>
+                write!(f, "{} is dealt twice", card),
+        }
+    }
+}
+
+// The four hands of a board. Hand records can leave hands out, so nothing
+// here is checked until `validate`.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub struct Deal {
+    hands_ : [Hand; 4],
+}
+
+fn seat_index(seat : Seat) -> usize {
+    SEATS.iter().position(|&s| s == seat).unwrap()
+}
+
+impl Deal {
+    pub fn new() -> Deal {
+        Deal {
+            hands_ : [Hand::new(); 4],
+        }
+    }
+
+    pub fn hand(&self, seat : Seat) -> &Hand {
+        &self.hands_[seat_index(seat)]
+    }
+
+    pub fn hand_mut(&mut self, seat : Seat) -> &mut Hand {
+        &mut self.hands_[seat_index(seat)]
+    }
+
+    pub fn holder(&self, card : Card) -> Option<Seat> {
+        SEATS.iter().cloned().find(|&s| self.hand(s).contains(card))
+    }
+
+    // Thirteen cards each and every card dealt exactly once.
+    pub fn validate(&self) -> Result<(), DealError> {
+        let mut seen = Hand::new();
+        for &seat in &SEATS {
+            for card in self.hand(seat).cards() {
+                if !seen.insert(card) {
+                    return Err(DealError::Duplicate(card));
+                }
+            }
+        }
+        for &seat in &SEATS {
+            let n = self.hand(seat).len();
+            if n != 13 {
+                return Err(DealError::WrongCount(seat, n));
+            }
+        }
+        Ok(())
+    }
+
+    // A PBN deal string starting with `first`, like "N:AKQ.32.T98.7654 ...".
+    // Empty hands are written as "-".
+    pub fn to_pbn(&self, first : Seat) -> String {
+        let mut seat = first;
+        let mut hands = Vec::new();
+        for _ in 0..4 {
+            let hand = self.hand(seat);
+            hands.push(if hand.is_empty() {
+                String::from("-")
+            } else {
+                hand.to_string()
+            });
+            seat = seat.next();
+        }
+        format!("{}:{}", first, hands.join(" "))
+    }
+
+    // North on top, West and East on either side and South below, a suit
+    // per line.
+    pub fn diagram(&self) -> String {
+        let lines = |seat : Seat| -> Vec<String> {
+            SUITS.iter().rev().map(|&s| {
+                let holding = self.hand(seat).holding(s);
+                format!("{} {}", s,
+                        if holding.is_empty() { "-" } else { &holding })
+            }).collect()
+        };
+        let mut out = String::new();
+        for line in lines(Seat::North) {
+            out += &format!("{:10}{}\n", "", line);
+        }
+        for (west, east) in lines(Seat::West).iter()
+            .zip(lines(Seat::East).iter()) {
+            out += &format!("{:20}{}\n", west, east);
+        }
+        for line in lines(Seat::South) {
+            out += &format!("{:10}{}\n", "", line);
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+const TEST_DEAL : &str =
+    "N:AKQJ.T98.765.432 T987.654.32.AKQJ 65432.AKQ.J4.T98 .J732.AKQT98.765";
+
+#[test]
+fn test_card_order() {
+    let ace = Card::new(Suit::Spades, Rank::Ace);
+    assert!(ace.index() == 51 && Card::from_index(51) == ace);
+    assert!(Card::new(Suit::Hearts, Rank::Two) > Card::new(Suit::Diamonds,
+                                                           Rank::Ace));
+    assert!(ace.to_string() == "SA");
+    assert!(Rank::from_char('t') == Some(Rank::Ten));
+}
+
+#[test]
+fn test_pbn_deal() {
+    let deal : Deal = TEST_DEAL.parse().unwrap();
+    assert!(deal.validate().is_ok());
+    assert!(deal.hand(Seat::West).length(Suit::Spades) == 0);
+    assert!(deal.hand(Seat::North).hcp() == 10);
+    assert!(deal.hand(Seat::North).is_balanced());
+    assert!(deal.hand(Seat::South).is_balanced());
+    assert!(deal.hand(Seat::East).is_balanced());
+    assert!(!deal.hand(Seat::West).is_balanced());
+    assert!(deal.hand(Seat::East).suit(Suit::Clubs) ==
+            vec![Rank::Ace, Rank::King, Rank::Queen, Rank::Jack]);
+    assert!(deal.holder(Card::new(Suit::Diamonds, Rank::Jack))
+            == Some(Seat::South));
+    assert!(deal.to_pbn(Seat::North) == TEST_DEAL);
+    let from_east : Deal = deal.to_pbn(Seat::East).parse().unwrap();
+    assert!(from_east == deal);
+    assert!(deal.diagram().lines().nth(4).unwrap().trim_end() ==
+            "S -                 S T987");
+}
+
+#[test]
+fn test_validate() {
+    let mut deal : Deal = TEST_DEAL.parse().unwrap();
+    let card = Card::new(Suit::Clubs, Rank::Two);
+    deal.hand_mut(Seat::North).remove(card);
+    assert!(deal.validate() == Err(DealError::WrongCount(Seat::North, 12)));
+    deal.hand_mut(Seat::North).insert(Card::new(Suit::Clubs, Rank::Ace));
+    assert!(deal.validate() ==
+            Err(DealError::Duplicate(Card::new(Suit::Clubs, Rank::Ace))));
+    let partial : Deal = "S:- - AKQJ.T98.765.432 -".parse().unwrap();
+    assert!(partial.hand(Seat::North).len() == 13);
+    assert!(partial.validate().is_err());
+    assert!("N:AKQJ.T98.765".parse::<Deal>().is_err());
+    assert!("N:AA.... - - -".parse::<Deal>().is_err());
+}
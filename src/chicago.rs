@@ -0,0 +1,99 @@
+use contract::Side;
+use data::Entry;
+use score::ScoringMode;
+
+// Chicago is played in wheels (or chukkers) of four deals with a fixed
+// vulnerability schedule: none, dealer's side, dealer's side, both.
+pub const DEALS_PER_WHEEL : u32 = 4;
+
+fn deal_in_wheel(board_num : u32) -> u32 {
+    (board_num - 1) % DEALS_PER_WHEEL
+}
+
+pub fn wheel(board_num : u32) -> u32 {
+    (board_num - 1) / DEALS_PER_WHEEL + 1
+}
+
+pub fn vulnerability(board_num : u32) -> (bool, bool) {
+    match deal_in_wheel(board_num) {
+        0 => (false, false),
+        3 => (true, true),
+        _ => match ScoringMode::Chicago.dealer(board_num).side() {
+            Side::NorthSouth => (true, false),
+            Side::EastWest   => (false, true),
+        },
+    }
+}
+
+// A part-score made on the last deal of the wheel is worth 100, not 50.
+pub fn part_score_bonus(board_num : u32) -> i32 {
+    if deal_in_wheel(board_num) == DEALS_PER_WHEEL - 1 { 100 } else { 50 }
+}
+
+// NS and EW points over a set of entries, usually one wheel. Undertricks
+// count for the defending side.
+pub fn wheel_totals(entries : &[Entry]) -> (i32, i32) {
+    let mut totals = (0, 0);
+    for score in entries.iter().filter_map(Entry::ns_score) {
+        if score >= 0 {
+            totals.0 += score;
+        } else {
+            totals.1 -= score;
+        }
+    }
+    totals
+}
+
+#[cfg(test)]
+use contract::Seat;
+#[cfg(test)]
+use data::Table;
+#[cfg(test)]
+use parse::parse_contract;
+
+#[test]
+fn test_vulnerability_schedule() {
+    assert!(vulnerability(1) == (false, false));
+    assert!(ScoringMode::Chicago.dealer(2) == Seat::East);
+    assert!(vulnerability(2) == (false, true));
+    assert!(vulnerability(3) == (true, false));
+    assert!(vulnerability(4) == (true, true));
+    assert!(vulnerability(5) == (false, false));
+    assert!(wheel(4) == 1 && wheel(5) == 2);
+}
+
+#[test]
+fn test_fourth_deal_part_score() {
+    let table = Table::new();
+    let mut entry = Entry::with_mode(&table, Seat::West, 4,
+                                     ScoringMode::Chicago);
+    entry.set_contract(parse_contract("2S").unwrap());
+    entry.record(0);
+    assert!(entry.is_vulnerable());
+    assert!(entry.value() == Some(160));
+
+    let mut entry = Entry::with_mode(&table, Seat::West, 3,
+                                     ScoringMode::Chicago);
+    entry.set_contract(parse_contract("2S").unwrap());
+    entry.record(0);
+    assert!(!entry.is_vulnerable());
+    assert!(entry.value() == Some(110));
+}
+
+#[test]
+fn test_wheel_totals() {
+    let table = Table::new();
+    let mut entries = Vec::new();
+    for &(seat, contract, margin) in &[(Seat::North, "3NT", 0),
+                                       (Seat::East, "4H", -1),
+                                       (Seat::South, "1C", 1)] {
+        let board = entries.len() as u32 + 1;
+        let mut entry = Entry::with_mode(&table, seat, board,
+                                         ScoringMode::Chicago);
+        entry.set_contract(parse_contract(contract).unwrap());
+        entry.record(margin);
+        entries.push(entry);
+    }
+    // 400 + 100 (vulnerable undertrick) + 90
+    assert!(wheel_totals(&entries) == (590, 0));
+}
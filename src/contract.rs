@@ -5,7 +5,59 @@ pub enum Seat {
     North, East, South, West
 }
 
+pub const SEATS : [Seat; 4] = [Seat::North, Seat::East, Seat::South, Seat::West];
+
+impl Seat {
+    // Clockwise, the order of play and of the auction.
+    pub fn next(self) -> Seat {
+        match self {
+            Seat::North => Seat::East,
+            Seat::East  => Seat::South,
+            Seat::South => Seat::West,
+            Seat::West  => Seat::North,
+        }
+    }
+
+    pub fn partner(self) -> Seat {
+        self.next().next()
+    }
+
+    pub fn side(self) -> Side {
+        match self {
+            Seat::North | Seat::South => Side::NorthSouth,
+            Seat::East  | Seat::West  => Side::EastWest,
+        }
+    }
+}
+
+impl fmt::Display for Seat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}",
+               match *self {
+                   Seat::North => "N",
+                   Seat::East  => "E",
+                   Seat::South => "S",
+                   Seat::West  => "W",
+               })
+    }
+}
+
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Side {
+    NorthSouth, EastWest
+}
+
+impl Side {
+    pub fn opponents(self) -> Side {
+        match self {
+            Side::NorthSouth => Side::EastWest,
+            Side::EastWest   => Side::NorthSouth,
+        }
+    }
+}
+
+// In bidding order.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
 pub enum ContractSuit {
     Clubs, Diamonds, Hearts, Spades, NoTrump
 }
@@ -23,7 +75,8 @@ impl fmt::Display for ContractSuit {
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+// In bidding order.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
 pub enum ContractNumber {
     One, Two, Three, Four, Five, Six, SEVEN
 }
@@ -81,9 +134,9 @@ impl Contract {
     pub fn new(suit : ContractSuit, number : ContractNumber,
                doubled : ContractDoubled) -> Contract {
         Contract {
-            suit : suit,
-            number : number,
-            doubled : doubled,
+            suit,
+            number,
+            doubled,
         }
     }
 }
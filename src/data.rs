@@ -1,5 +1,10 @@
-use contract::{Contract, Seat};
-use score::Score;
+use cards::{Card, Deal};
+use contract::{Contract, Seat, Side};
+use dds::DdTable;
+use imp::Room;
+use par::{self, Par};
+use play::{Play, PlayError};
+use score::{Honors, HonorsError, Score, ScoringMode};
 
 pub struct Table {
     players_ : [String; 4],
@@ -36,6 +41,13 @@ impl Table {
     }
 }
 
+impl Default for Table {
+    fn default() -> Table {
+        Table::new()
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
 pub struct Entry {
     declarer_ : Seat,
     name_ : String,
@@ -45,24 +57,56 @@ pub struct Entry {
     ew_vulnerable_ : bool,
     result_ : Option<i32>,
     value_ : Option<i32>,
+    mode_ : ScoringMode,
+    honors_ : Option<Honors>,
+    room_ : Option<Room>,
+    deal_ : Option<Deal>,
+    play_ : Option<Play>,
 }
 
 impl Entry {
     pub fn new(table : &Table, declarer : Seat, board_num : u32) -> Entry {
+        Entry::with_mode(table, declarer, board_num, ScoringMode::Duplicate)
+    }
+
+    pub fn with_mode(table : &Table, declarer : Seat, board_num : u32,
+                     mode : ScoringMode) -> Entry {
+        let (ns_vulnerable, ew_vulnerable) = mode.vulnerability(board_num);
         Entry {
             name_ : String::from(table.get_player(declarer)),
             declarer_ : declarer,
             contract_ : None,
             board_num_ : board_num,
-            ns_vulnerable_ : ((board_num - 1) & 1 == 1),
-            ew_vulnerable_ : ((board_num - 1) & 2 == 2),
+            ns_vulnerable_ : ns_vulnerable,
+            ew_vulnerable_ : ew_vulnerable,
             result_ : None,
             value_ : None,
+            mode_ : mode,
+            honors_ : None,
+            room_ : None,
+            deal_ : None,
+            play_ : None,
         }
     }
 
+    pub fn mode(&self) -> ScoringMode {
+        self.mode_
+    }
+
+    // Switching modes resets the vulnerability to the new mode's schedule.
+    pub fn set_mode(&mut self, mode : ScoringMode) {
+        let (ns_vulnerable, ew_vulnerable) = mode.vulnerability(self.board_num_);
+        self.mode_ = mode;
+        self.set_vulnerability(ns_vulnerable, ew_vulnerable);
+    }
+
+    // Honors that don't fit the new strain are dropped.
     pub fn set_contract(&mut self, c : Contract) {
         self.contract_ = Some(c);
+        self.check_play();
+        if self.honors_.is_some_and(|h| h.check(c.suit).is_err()) {
+            self.honors_ = None;
+        }
         self.recompute();
     }
 
@@ -78,6 +122,19 @@ impl Entry {
         self.board_num_
     }
 
+    // Vulnerability follows the new board. False for board 0, which
+    // doesn't exist.
+    pub fn set_board_num(&mut self, board_num : u32) -> bool {
+        if board_num == 0 {
+            return false;
+        }
+        let (ns_vulnerable, ew_vulnerable) =
+            self.mode_.vulnerability(board_num);
+        self.board_num_ = board_num;
+        self.set_vulnerability(ns_vulnerable, ew_vulnerable);
+        true
+    }
+
     pub fn is_vulnerable(&self) -> bool {
         match self.declarer_ {
             Seat::North | Seat::South => self.ns_vulnerable_,
@@ -93,20 +150,61 @@ impl Entry {
         self.recompute();
     }
 
+    // NS and EW vulnerability.
+    pub fn vulnerability(&self) -> (bool, bool) {
+        (self.ns_vulnerable_, self.ew_vulnerable_)
+    }
+
+    pub fn set_vulnerability(&mut self, ns_vulnerable : bool,
+                             ew_vulnerable : bool) {
+        self.ns_vulnerable_ = ns_vulnerable;
+        self.ew_vulnerable_ = ew_vulnerable;
+        self.recompute();
+    }
+
+    pub fn honors(&self) -> Option<Honors> {
+        self.honors_
+    }
+
+    // Honors can only be checked against a contract, so one has to be set.
+    pub fn set_honors(&mut self, honors : Option<Honors>)
+        -> Result<(), HonorsError> {
+        if let (Some(h), Some(c)) = (honors, self.contract_) {
+            h.check(c.suit)?;
+        } else if honors.is_some() {
+            return Err(HonorsError::NoContract);
+        }
+        self.honors_ = honors;
+        self.recompute();
+        Ok(())
+    }
+
+    // A result typed in by hand replaces a finished play log that says
+    // otherwise.
     pub fn record(&mut self, margin : i32) {
+        if self.play_.as_ref()
+            .is_some_and(|p| p.is_finished() && p.result() != Some(margin)) {
+            self.play_ = None;
+        }
         self.result_ = Some(margin);
         self.recompute();
     }
 
     fn recompute(&mut self) {
-        self.value_ = match (self.contract_.as_ref(), self.result_) {
-            (Some(c), Some(r)) =>
-                Some(Score::from_contract(c,
-                                          self.is_vulnerable()).score_result(r)),
-            _ => None,
+        self.value_ = match (self.score(), self.result_) {
+            (Some(s), Some(r)) => Some(s.score_result(r)),
+            _                  => None,
         };
     }
 
+    // How the contract scores, whatever the result.
+    pub fn score(&self) -> Option<Score> {
+        self.contract_.as_ref().map(|c| {
+            self.mode_.score(c, self.is_vulnerable(), self.board_num_,
+                             self.honors_)
+        })
+    }
+
     pub fn name(&self) -> &str {
         &self.name_
     }
@@ -123,7 +221,99 @@ impl Entry {
         self.value_
     }
 
+    pub fn room(&self) -> Option<Room> {
+        self.room_
+    }
+
+    pub fn set_room(&mut self, room : Option<Room>) {
+        self.room_ = room
+    }
+
+    pub fn deal(&self) -> Option<&Deal> {
+        self.deal_.as_ref()
+    }
+
+    pub fn set_deal(&mut self, deal : Option<Deal>) {
+        self.deal_ = deal;
+        self.check_play();
+    }
+
+    pub fn play(&self) -> Option<&Play> {
+        self.play_.as_ref()
+    }
+
+    // The first card starts the play log, which needs a full deal and a
+    // contract. Once play is over it decides the result.
+    pub fn play_card(&mut self, card : Card) -> Result<(), PlayError> {
+        self.play_mut()?.play(card)?;
+        self.play_result();
+        Ok(())
+    }
+
+    pub fn claim(&mut self, tricks : u32) -> Result<(), PlayError> {
+        self.play_mut()?.claim(tricks)?;
+        self.play_result();
+        Ok(())
+    }
+
+    pub fn clear_play(&mut self) {
+        self.play_ = None;
+    }
+
+    fn play_mut(&mut self) -> Result<&mut Play, PlayError> {
+        if self.play_.is_none() {
+            let contract = self.contract_.ok_or(PlayError::NoContract)?;
+            let deal = self.deal_.ok_or(PlayError::NoDeal)?;
+            self.play_ = Some(Play::new(deal, contract, self.declarer_)
+                              .map_err(|_| PlayError::NoDeal)?);
+        }
+        Ok(self.play_.as_mut().unwrap())
+    }
+
+    fn play_result(&mut self) {
+        if let Some(r) = self.play_.as_ref().and_then(Play::result) {
+            self.result_ = Some(r);
+            self.recompute();
+        }
+    }
+
+    // A play log only stands for the deal, contract and declarer it was
+    // played with.
+    fn check_play(&mut self) {
+        let stale = self.play_.as_ref().is_some_and(|p| {
+            Some(*p.deal()) != self.deal_ ||
+                Some(p.contract()) != self.contract_ ||
+                p.declarer() != self.declarer_
+        });
+        if stale {
+            self.play_ = None;
+        }
+    }
+
     pub fn declarer(&self) -> Option<Seat> {
         Some(self.declarer_)
     }
+
+    // The name follows the new declarer, and so does the vulnerability,
+    // which is kept for both sides.
+    pub fn set_declarer(&mut self, table : &Table, declarer : Seat) {
+        self.declarer_ = declarer;
+        self.name_ = String::from(table.get_player(declarer));
+        self.check_play();
+        self.recompute();
+    }
+
+    // Par at this board's vulnerability, with the dealer bidding first.
+    pub fn par(&self, table : &DdTable) -> Par {
+        par::par(table, self.vulnerability(),
+                 self.mode_.dealer(self.board_num_))
+    }
+
+    // The value from North-South's point of view.
+    pub fn ns_score(&self) -> Option<i32> {
+        self.value_.map(|v| match self.declarer_.side() {
+            Side::NorthSouth => v,
+            Side::EastWest   => -v,
+        })
+    }
 }
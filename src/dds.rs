@@ -0,0 +1,766 @@
+use std::collections::HashMap;
+use std::hash::{BuildHasherDefault, Hasher};
+use std::thread;
+
+use cards::{Deal, Suit};
+use contract::{ContractSuit, Seat, Side, SEATS};
+
+// Double-dummy analysis: the tricks each declarer can make in each strain
+// with every card in view and best play from both sides.
+
+pub const STRAINS : [ContractSuit; 5] =
+    [ContractSuit::Clubs, ContractSuit::Diamonds, ContractSuit::Hearts,
+     ContractSuit::Spades, ContractSuit::NoTrump];
+
+fn seat_index(seat : Seat) -> usize {
+    SEATS.iter().position(|&s| s == seat).unwrap()
+}
+
+fn strain_index(strain : ContractSuit) -> usize {
+    STRAINS.iter().position(|&s| s == strain).unwrap()
+}
+
+// Tricks for every declarer and strain.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct DdTable {
+    tricks_ : [[u8; 5]; 4],
+}
+
+impl DdTable {
+    // Tricks by declarer in `SEATS` order, then by strain in `STRAINS` order.
+    pub fn new(tricks : [[u8; 5]; 4]) -> DdTable {
+        DdTable {
+            tricks_ : tricks,
+        }
+    }
+
+    pub fn tricks(&self, declarer : Seat, strain : ContractSuit) -> u8 {
+        self.tricks_[seat_index(declarer)][strain_index(strain)]
+    }
+}
+
+// Keys are already well-mixed bit patterns, so a multiply and a shift
+// are all the hashing they need.
+#[derive(Default)]
+struct KeyHasher {
+    hash_ : u64,
+}
+
+impl Hasher for KeyHasher {
+    fn finish(&self) -> u64 {
+        self.hash_
+    }
+
+    fn write(&mut self, bytes : &[u8]) {
+        for &b in bytes {
+            self.hash_ = (self.hash_.rotate_left(8) ^ b as u64)
+                .wrapping_mul(0x9e37_79b9_7f4a_7c15);
+        }
+    }
+
+    fn write_u128(&mut self, n : u128) {
+        let folded = (n as u64) ^ ((n >> 64) as u64).rotate_left(29);
+        let hash = folded.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+        self.hash_ = hash ^ (hash >> 32);
+    }
+}
+
+fn suit_mask(suit : usize) -> u64 {
+    0x1fff << (suit * 13)
+}
+
+fn highest(cards : u64) -> usize {
+    63 - cards.leading_zeros() as usize
+}
+
+// What one search showed about a position: North-South take between
+// `low_` and `high_` of the tricks left. Only the top few cards of each
+// suit ever decided a trick, so the result holds for any position with the
+// same suit lengths where the same seats hold those cards.
+struct Bound {
+    // How many of the top cards of each suit decided it, four bits a suit,
+    // and which seats held them, as in `Solver::holders_`.
+    depths_ : u16,
+    holders_ : u128,
+    low_ : i8,
+    high_ : i8,
+    // The lead that won, if one did, to try first when searching here again.
+    best_ : Option<u8>,
+}
+
+impl Bound {
+    fn depth(&self, suit : usize) -> u32 {
+        (self.depths_ >> (suit * 4)) as u32 & 0xf
+    }
+
+    // The part of `Solver::holders_` that has to match.
+    fn mask(&self) -> u128 {
+        (0..4).fold(0, |mask, suit| {
+            mask | ((1 << (2 * self.depth(suit))) - 1) << (suit * 32)
+        })
+    }
+}
+
+// The bounds stored under one key. Alongside each is the part of it that
+// covers the top four cards of each suit, packed small, so that most of the
+// ones that don't match are passed over without reading the rest.
+#[derive(Default)]
+struct Bucket {
+    tops_ : Vec<u64>,
+    bounds_ : Vec<Bound>,
+}
+
+// The top four cards of each suit from `holders`, eight bits a suit.
+fn tops(holders : u128) -> u32 {
+    let mut packed = 0;
+    for suit in 0..4 {
+        packed |= ((holders >> (suit * 32)) as u32 & 0xff) << (suit * 8);
+    }
+    packed
+}
+
+// Searches one strain, with cards as indexes 0 to 51 and seats as indexes
+// into `SEATS`, so North-South are the even ones. Positions are only
+// stored at the start of a trick. Alongside each result the search works
+// out which cards won a trick by rank, so that one entry covers every
+// position that differs only in the small cards.
+struct Solver {
+    hands_ : [u64; 4],
+    // Kept in step with `hands_`: four bits for each hand's length in each
+    // suit, and the seat holding each card still out, two bits per card and
+    // 32 bits per suit, with the highest card of each suit in the lowest
+    // bits.
+    lengths_ : u128,
+    holders_ : u128,
+    trump_ : Option<usize>,
+    // The cards played to the current trick, with the seat of each.
+    trick_ : [(usize, usize); 4],
+    played_ : usize,
+    // The last lead to cut a search short.
+    best_ : Option<usize>,
+    // How much searching each lead has cut short, by seat and card.
+    history_ : [[u32; 52]; 4],
+    // North-South's tricks from the last search.
+    guess_ : i32,
+    // Results by leader and suit lengths.
+    bounds_ : HashMap<u128, Bucket, BuildHasherDefault<KeyHasher>>,
+}
+
+impl Solver {
+    fn new(deal : &Deal, strain : ContractSuit) -> Solver {
+        let mut hands = [0u64; 4];
+        for &seat in &SEATS {
+            for card in deal.hand(seat).cards() {
+                hands[seat_index(seat)] |= 1 << card.index();
+            }
+        }
+        let mut solver = Solver {
+            hands_ : [0; 4],
+            lengths_ : 0,
+            holders_ : 0,
+            trump_ : Suit::from_strain(strain).map(Suit::index),
+            trick_ : [(0, 0); 4],
+            played_ : 0,
+            best_ : None,
+            history_ : [[0; 52]; 4],
+            guess_ : hands[0].count_ones() as i32 / 2,
+            bounds_ : HashMap::default(),
+        };
+        for (seat, &hand) in hands.iter().enumerate() {
+            let mut cards = hand;
+            while cards != 0 {
+                let ix = highest(cards);
+                cards &= !(1 << ix);
+                solver.put_back(seat, ix);
+            }
+        }
+        solver
+    }
+
+    fn remaining(&self) -> i32 {
+        self.hands_[0].count_ones() as i32
+    }
+
+    fn all(&self) -> u64 {
+        self.hands_[0] | self.hands_[1] | self.hands_[2] | self.hands_[3]
+    }
+
+    fn key(&self, leader : usize) -> u128 {
+        (leader as u128) << 64 | self.lengths_
+    }
+
+    // Where `card` goes in `holders_`: the bits below it stay put, and the
+    // ones from there to the end of its suit move.
+    fn slot(&self, card : usize) -> (u32, u128, u128) {
+        let suit = card / 13;
+        let above = self.all() & suit_mask(suit) & !((2 << card) - 1);
+        let shift = (suit * 32) as u32 + 2 * above.count_ones();
+        let lane = 0xffff_ffff << (suit * 32);
+        (shift, lane & ((1 << shift) - 1), lane & !((1 << shift) - 1))
+    }
+
+    fn take(&mut self, seat : usize, card : usize) {
+        let (_, _, moved) = self.slot(card);
+        self.hands_[seat] &= !(1 << card);
+        self.lengths_ -= 1 << (4 * (15 - 4 * seat - card / 13));
+        self.holders_ = self.holders_ & !moved |
+            (self.holders_ & moved) >> 2 & moved;
+    }
+
+    fn put_back(&mut self, seat : usize, card : usize) {
+        let (shift, _, moved) = self.slot(card);
+        self.hands_[seat] |= 1 << card;
+        self.lengths_ += 1 << (4 * (15 - 4 * seat - card / 13));
+        self.holders_ = self.holders_ & !moved |
+            (self.holders_ & moved) << 2 & moved | (seat as u128) << shift;
+    }
+
+    // The cards still out that decided `bound`.
+    fn top_cards(&self, bound : &Bound) -> u64 {
+        let all = self.all();
+        let mut top = 0;
+        for suit in 0..4 {
+            let mut cards = all & suit_mask(suit);
+            for _ in 0..bound.depth(suit) {
+                let ix = highest(cards);
+                cards &= !(1 << ix);
+                top |= 1 << ix;
+            }
+        }
+        top
+    }
+
+    fn beats(&self, card : usize, best : usize) -> bool {
+        if card / 13 == best / 13 {
+            card > best
+        } else {
+            Some(card / 13) == self.trump_
+        }
+    }
+
+    // The seat and card currently winning the trick.
+    fn winning(&self) -> (usize, usize) {
+        let mut best = self.trick_[0];
+        for &played in &self.trick_[1..self.played_] {
+            if self.beats(played.1, best.1) {
+                best = played;
+            }
+        }
+        best
+    }
+
+    // The top cards the leader's side can cash for `needed` tricks without
+    // losing the lead, crossing between the two hands where that helps. A
+    // suit is only led while every opponent who still has trumps has to
+    // follow to it. Gives the cards that won.
+    fn quick_tricks(&self, leader : usize, needed : i32) -> Option<u64> {
+        let mut hands = self.hands_;
+        let mut seat = leader;
+        let mut tricks = 0;
+        let mut winners = 0;
+        loop {
+            let partner = (seat + 2) % 4;
+            let opponents = hands[(seat + 1) % 4] | hands[(seat + 3) % 4];
+            let all = opponents | hands[seat] | hands[partner];
+            let safe = |suit : usize| {
+                self.trump_.is_none_or(|t| {
+                    t == suit || [(seat + 1) % 4, (seat + 3) % 4].iter()
+                        .all(|&o| hands[o] & suit_mask(t) == 0 ||
+                             hands[o] & suit_mask(suit) != 0)
+                })
+            };
+            let top = |suit : usize| {
+                let cards = all & suit_mask(suit);
+                if cards == 0 { 0 } else { 1 << highest(cards) }
+            };
+            // Partner has to waste a winner under this one.
+            let wastes = |suit : usize| {
+                let theirs = hands[partner] & suit_mask(suit);
+                let against = opponents & suit_mask(suit);
+                theirs != 0 && (against == 0 ||
+                                theirs & ((1 << highest(against)) - 1) == 0)
+            };
+            let cash = (0..4)
+                .filter(|&s| safe(s) && hands[seat] & top(s) != 0)
+                .min_by_key(|&s| wastes(s));
+            // Otherwise lead low to partner's top card.
+            let cross = || (0..4).find(|&s| {
+                safe(s) && hands[partner] & top(s) != 0 &&
+                    hands[seat] & suit_mask(s) != 0
+            });
+            // Or lead one partner can ruff without being overruffed.
+            let ruff = || self.trump_.and_then(|t| (0..4).find(|&s| {
+                s != t && safe(s) && hands[seat] & suit_mask(s) != 0 &&
+                    hands[partner] & suit_mask(s) == 0 &&
+                    hands[partner] & suit_mask(t) != 0
+            }));
+            let (suit, card) = if let Some(s) = cash.or_else(cross) {
+                // Its rank only matters if some other hand has the suit.
+                let holder =
+                    if hands[seat] & top(s) != 0 { seat } else { partner };
+                if all & suit_mask(s) & !hands[holder] != 0 {
+                    winners |= top(s);
+                }
+                (suit_mask(s), top(s))
+            } else if let Some(s) = ruff() {
+                // No rank matters when the only trump played wins.
+                let trumps = hands[partner] & suit_mask(self.trump_?);
+                (suit_mask(s), trumps & trumps.wrapping_neg())
+            } else {
+                return None;
+            };
+            let (winner, other) = if hands[seat] & card != 0 {
+                (seat, partner)
+            } else {
+                (partner, seat)
+            };
+            // Partner throws its lowest card when it can't follow.
+            if hands[other] & suit == 0 {
+                hands[other] &= hands[other].wrapping_sub(1);
+            }
+            for hand in hands.iter_mut() {
+                let cards = *hand & suit;
+                *hand &= !(if *hand & card != 0 { card }
+                           else { cards & cards.wrapping_neg() });
+            }
+            seat = winner;
+            tricks += 1;
+            if tricks >= needed {
+                return Some(winners);
+            }
+        }
+    }
+
+    // Whether `seat`'s side makes `needed` trump tricks whatever happens,
+    // as it does when either hand holds that many trumps over the other
+    // side's: each one wins the trick it is played to. Gives the trumps
+    // that decide this.
+    fn sure_trumps(&self, seat : usize, needed : i32) -> Option<u64> {
+        let trumps = suit_mask(self.trump_?);
+        let theirs = (self.hands_[(seat + 1) % 4] |
+                      self.hands_[(seat + 3) % 4]) & trumps;
+        let above = if theirs == 0 {
+            trumps
+        } else {
+            trumps & !((2 << highest(theirs)) - 1)
+        };
+        for &hand in &[self.hands_[seat], self.hands_[(seat + 2) % 4]] {
+            let mut counted = hand & above;
+            if (counted.count_ones() as i32) < needed {
+                continue;
+            }
+            if theirs == 0 {
+                return Some(0);
+            }
+            for _ in 1..needed {
+                counted &= !(1 << highest(counted));
+            }
+            return Some(self.all() & trumps &
+                        !((1 << highest(counted)) - 1));
+        }
+        None
+    }
+
+    // Can `seat` beat `best` when its turn comes in this trick?
+    fn can_beat(&self, seat : usize, best : usize) -> bool {
+        let hand = self.hands_[seat];
+        let led = self.trick_[0].1 / 13;
+        if hand & suit_mask(led) != 0 {
+            best / 13 == led && hand & !((2 << best) - 1) & suit_mask(led) != 0
+        } else {
+            self.trump_.is_some_and(|t| {
+                let trumps = hand & suit_mask(t);
+                trumps != 0 && (best / 13 != t || highest(trumps) > best)
+            })
+        }
+    }
+
+    // How promising a lead is, lowest first. Quick tricks have already
+    // been tried by now, so winners are better kept back, and a lead the
+    // opponents can ruff comes last.
+    fn lead_priority(&self, seat : usize, ix : usize, live : u64) -> usize {
+        let suit = ix / 13;
+        let above = live & suit_mask(suit) & !((2 << ix) - 1);
+        if above & !self.hands_[seat] == 0 {
+            return 1;
+        }
+        if let Some(t) = self.trump_.filter(|&t| t != suit) {
+            let ruffs = |s : usize| {
+                self.hands_[s] & suit_mask(suit) == 0 &&
+                    self.hands_[s] & suit_mask(t) != 0
+            };
+            if ruffs((seat + 1) % 4) || ruffs((seat + 3) % 4) {
+                return 2;
+            }
+        }
+        0
+    }
+
+    // The lowest card from each run of touching cards, in the order most
+    // likely to cut the search short. Cards already played to the trick
+    // don't break up a run unless they decide which of two cards wins.
+    fn moves(&self, seat : usize, first : Option<usize>,
+             out : &mut [usize; 13]) -> usize {
+        let hand = self.hands_[seat];
+        let legal = match self.played_ {
+            0 => hand,
+            _ => {
+                let led = suit_mask(self.trick_[0].1 / 13);
+                if hand & led != 0 { hand & led } else { hand }
+            },
+        };
+        let mut live = self.all();
+        for &(_, card) in &self.trick_[..self.played_] {
+            live |= 1 << card;
+        }
+        let (winner, best) = if self.played_ > 0 {
+            self.winning()
+        } else {
+            (seat, 0)
+        };
+        let partner_winning = self.played_ > 0 && winner % 2 == seat % 2;
+        let partner = self.hands_[(seat + 2) % 4];
+        let partner_top = self.played_ == 1 && {
+            let led = live & suit_mask(self.trick_[0].1 / 13);
+            partner & (1 << highest(led)) != 0
+        };
+        let overtaken =
+            self.played_ == 2 && self.can_beat((seat + 1) % 4, best);
+        let mut n = 0;
+        let mut ranked = [0; 13];
+        let mut cards = legal;
+        while cards != 0 {
+            let ix = highest(cards);
+            cards &= !(1 << ix);
+            let mask = suit_mask(ix / 13);
+            let wins = self.played_ > 0 && self.beats(ix, best);
+            let below = self.all() & mask & ((1 << ix) - 1);
+            if below != 0 && legal & (1 << highest(below)) != 0 &&
+                (self.played_ == 0 ||
+                 self.beats(highest(below), best) == wins) {
+                continue;
+            }
+            let priority = match self.played_ {
+                0 => self.lead_priority(seat, ix, live),
+                _ if partner_winning && !overtaken => if wins { 2 } else { 0 },
+                // Second hand takes the trick where it can, unless partner
+                // holds the top card.
+                1 => {
+                    let above = live & mask & !((2 << ix) - 1);
+                    if wins && above & !hand == 0 { 0 }
+                    else if partner_top { wins as usize }
+                    else if wins { 1 }
+                    else { 2 }
+                },
+                _ => if wins { 0 } else { 1 },
+            };
+            // Sort keys, lowest first, with the card in the bottom six bits.
+            ranked[n] = if first == Some(ix) {
+                ix as u64
+            } else if self.played_ == 0 {
+                // Among leads, the ones that have worked best so far, then
+                // the suits the opponents hold more of.
+                let ours = ((hand | partner) & mask).count_ones() as u64;
+                let history = u32::MAX - self.history_[seat][ix];
+                (priority as u64 + 1) << 48 | (history as u64) << 16 |
+                    (ours * 16 + (ix % 13) as u64) << 6 | ix as u64
+            } else {
+                (priority as u64 + 1) << 48 | ((ix % 13) as u64) << 6 |
+                    ix as u64
+            };
+            n += 1;
+        }
+        ranked[..n].sort_unstable();
+        for (slot, &key) in out.iter_mut().zip(ranked[..n].iter()) {
+            *slot = (key & 0x3f) as usize;
+        }
+        n
+    }
+
+    // Can North-South take `target` of the tricks still to play, with
+    // `leader` on lead to a new trick? Also gives the cards whose rank
+    // decided the answer.
+    fn can_take(&mut self, leader : usize, target : i32) -> (bool, u64) {
+        let remaining = self.remaining();
+        if target <= 0 {
+            return (true, 0);
+        }
+        if target > remaining {
+            return (false, 0);
+        }
+        if let Some(decided) = self.sure_trumps(0, target) {
+            return (true, decided);
+        }
+        if let Some(decided) = self.sure_trumps(1, remaining - target + 1) {
+            return (false, decided);
+        }
+        if leader.is_multiple_of(2) {
+            if let Some(winners) = self.quick_tricks(leader, target) {
+                return (true, winners);
+            }
+        } else if let Some(winners) =
+            self.quick_tricks(leader, remaining - target + 1) {
+            return (false, winners);
+        }
+        let key = self.key(leader);
+        let mut first = None;
+        if let Some(bucket) = self.bounds_.get(&key) {
+            let holders = self.holders_;
+            let top = tops(holders);
+            // Newest first, since they are likeliest to be close by.
+            for (b, &t) in bucket.bounds_.iter().zip(&bucket.tops_).rev() {
+                if top & (t >> 32) as u32 != t as u32 ||
+                    holders & b.mask() != b.holders_ {
+                    continue;
+                }
+                if b.low_ as i32 >= target || (b.high_ as i32) < target {
+                    return (b.low_ as i32 >= target, self.top_cards(b));
+                }
+                first = first.or(b.best_);
+            }
+        }
+        let first = first.map(usize::from)
+            .filter(|&c| self.hands_[leader] & (1 << c) != 0);
+        self.best_ = None;
+        let (result, ranks) = self.play(leader, target, first);
+        let all = self.all();
+        let mut depths = 0;
+        for suit in 0..4 {
+            let cards = ranks & suit_mask(suit);
+            if cards != 0 {
+                let lowest = cards.trailing_zeros();
+                let n = (all & suit_mask(suit) & !((1 << lowest) - 1))
+                    .count_ones();
+                depths |= (n as u16) << (suit * 4);
+            }
+        }
+        let mut bound = Bound {
+            depths_ : depths,
+            holders_ : self.holders_,
+            low_ : if result { target } else { 0 } as i8,
+            high_ : if result { remaining } else { target - 1 } as i8,
+            best_ : self.best_.map(|c| c as u8),
+        };
+        let mask = bound.mask();
+        bound.holders_ &= mask;
+        let bucket = self.bounds_.entry(key).or_default();
+        let top = (tops(mask) as u64) << 32 | tops(bound.holders_) as u64;
+        match bucket.bounds_.iter_mut().zip(&bucket.tops_).find(|&(ref b, &t)| {
+            t == top && b.depths_ == depths && b.holders_ == bound.holders_
+        }) {
+            Some((b, _)) => {
+                b.low_ = b.low_.max(bound.low_);
+                b.high_ = b.high_.min(bound.high_);
+                b.best_ = bound.best_.or(b.best_);
+            },
+            None => {
+                bucket.tops_.push(top);
+                bucket.bounds_.push(bound);
+            },
+        }
+        (result, ranks)
+    }
+
+    // Tries each of `seat`'s cards in turn, starting with `first` if given.
+    fn play(&mut self, seat : usize, target : i32, first : Option<usize>)
+        -> (bool, u64) {
+        let north_south = seat.is_multiple_of(2);
+        let mut moves = [0; 13];
+        let n = self.moves(seat, first, &mut moves);
+        let mut ranks = 0;
+        let mut skip = 0;
+        for &card in &moves[..n] {
+            if skip & (1 << card) != 0 {
+                continue;
+            }
+            self.take(seat, card);
+            self.trick_[self.played_] = (seat, card);
+            self.played_ += 1;
+            let (result, decided) = if self.played_ == 4 {
+                let (winner, best) = self.winning();
+                let won = if winner % 2 == 0 { 1 } else { 0 };
+                let trick = self.trick_;
+                self.played_ = 0;
+                let (result, mut decided) =
+                    self.can_take(winner, target - won);
+                self.trick_ = trick;
+                self.played_ = 4;
+                // The winner's rank only matters if it beat another card of
+                // its suit.
+                if trick.iter().filter(|p| p.1 / 13 == best / 13).count() > 1 {
+                    decided |= 1 << best;
+                }
+                (result, decided)
+            } else {
+                self.play((seat + 1) % 4, target, None)
+            };
+            self.played_ -= 1;
+            self.put_back(seat, card);
+            if result == north_south {
+                if self.played_ == 0 {
+                    // Cuts near the top of the search save the most.
+                    let remaining = self.remaining() as u32;
+                    self.history_[seat][card] = self.history_[seat][card]
+                        .saturating_add(remaining * remaining);
+                    self.best_ = Some(card);
+                }
+                return (result, decided);
+            }
+            ranks |= decided;
+            let suit = suit_mask(card / 13);
+            // Cards of this suit below every one that mattered would have
+            // done the same, so they need no search of their own.
+            let relevant = decided & suit;
+            let low = if relevant == 0 {
+                1 << 52
+            } else {
+                1 << relevant.trailing_zeros()
+            };
+            if (1 << card) < low {
+                skip |= suit & (low - 1);
+            }
+        }
+        if self.played_ == 0 {
+            self.best_ = None;
+        }
+        (!north_south, ranks)
+    }
+
+    // North-South's tricks with `leader` on lead. Searching near the
+    // answer is cheap, so this steps out from the last one.
+    fn north_south_tricks(&mut self, leader : usize) -> i32 {
+        let mut tricks = self.guess_.min(self.remaining());
+        if self.can_take(leader, tricks).0 {
+            while tricks < self.remaining() &&
+                self.can_take(leader, tricks + 1).0 {
+                tricks += 1;
+            }
+        } else {
+            tricks -= 1;
+            while !self.can_take(leader, tricks).0 {
+                tricks -= 1;
+            }
+        }
+        self.guess_ = tricks;
+        tricks
+    }
+}
+
+// Tricks `declarer` makes in `strain`, out of however many cards each hand
+// holds. The hands all have to be the same size.
+pub fn declarer_tricks(deal : &Deal, strain : ContractSuit, declarer : Seat)
+    -> Option<u8> {
+    tricks_with(&mut Solver::new(deal, strain), deal, declarer)
+}
+
+fn tricks_with(solver : &mut Solver, deal : &Deal, declarer : Seat)
+    -> Option<u8> {
+    let size = deal.hand(Seat::North).len();
+    if SEATS.iter().any(|&s| deal.hand(s).len() != size) {
+        return None;
+    }
+    let ns = solver.north_south_tricks(seat_index(declarer.next()));
+    Some(match declarer.side() {
+        Side::NorthSouth => ns,
+        Side::EastWest   => size as i32 - ns,
+    } as u8)
+}
+
+pub fn solve(deal : &Deal) -> Option<DdTable> {
+    // The strains have nothing in common, so each gets a thread of its own.
+    let columns : Vec<Option<[u8; 4]>> = thread::scope(|scope| {
+        let solvers : Vec<_> = STRAINS.iter().map(|&strain| {
+            scope.spawn(move || {
+                // Positions carry over between leaders in the same strain.
+                let mut solver = Solver::new(deal, strain);
+                let mut column = [0; 4];
+                for &declarer in &SEATS {
+                    column[seat_index(declarer)] =
+                        tricks_with(&mut solver, deal, declarer)?;
+                }
+                Some(column)
+            })
+        }).collect();
+        solvers.into_iter().map(|s| s.join().unwrap()).collect()
+    });
+    let mut table = DdTable::new([[0; 5]; 4]);
+    for (strain, column) in columns.into_iter().enumerate() {
+        for (seat, &tricks) in column?.iter().enumerate() {
+            table.tricks_[seat][strain] = tricks;
+        }
+    }
+    Some(table)
+}
+
+#[cfg(test)]
+fn ending(text : &str) -> Deal {
+    text.parse().unwrap()
+}
+
+#[test]
+fn test_ruffing() {
+    let deal = ending("N:AK... .AK.. .32.. ..32.");
+    let expected = [(ContractSuit::NoTrump,  [0, 2, 0, 0]),
+                    (ContractSuit::Spades,   [2, 0, 2, 0]),
+                    (ContractSuit::Hearts,   [0, 2, 0, 2]),
+                    (ContractSuit::Diamonds, [0, 2, 0, 2]),
+                    (ContractSuit::Clubs,    [0, 2, 0, 0])];
+    let table = solve(&deal).unwrap();
+    for &(strain, tricks) in &expected {
+        for (&seat, &n) in SEATS.iter().zip(tricks.iter()) {
+            assert!(table.tricks(seat, strain) == n);
+        }
+    }
+}
+
+#[test]
+fn test_squeeze() {
+    // South cashes the club ace and West can't keep both the spade king
+    // and the guarded heart king.
+    let deal = ending("N:Q.AQ.. ..543. .2.2.A K.K4..");
+    assert!(declarer_tricks(&deal, ContractSuit::NoTrump, Seat::East)
+            == Some(0));
+    // With North on lead South never gets to cash it.
+    assert!(declarer_tricks(&deal, ContractSuit::NoTrump, Seat::West)
+            == Some(1));
+}
+
+#[test]
+fn test_unblock() {
+    // West has to drop the spade king under North's ace, or it crashes
+    // with East's queen.
+    let deal = ending("N:AJT5... Q943... 76.JT.. K8.98..");
+    assert!(declarer_tricks(&deal, ContractSuit::NoTrump, Seat::West)
+            == Some(2));
+}
+
+#[test]
+fn test_full_deal() {
+    let deal = ending("N:AKQJT98765432... .AKQJT98765432.. \
+                       ..AKQJT98765432. ...AKQJT98765432");
+    let table = solve(&deal).unwrap();
+    for &seat in &SEATS {
+        // The opening leader runs their suit.
+        assert!(table.tricks(seat, ContractSuit::NoTrump) == 0);
+    }
+    assert!(table.tricks(Seat::North, ContractSuit::Spades) == 13);
+    assert!(table.tricks(Seat::South, ContractSuit::Spades) == 13);
+    assert!(table.tricks(Seat::West, ContractSuit::Spades) == 0);
+    assert!(table.tricks(Seat::East, ContractSuit::Hearts) == 13);
+    assert!(solve(&ending("N:A... K... Q... -")).is_none());
+}
+
+// Times a session's worth of random deals. Only meaningful in a release
+// build: cargo test --release -- --ignored
+#[test]
+#[ignore]
+fn test_session_time() {
+    use dealgen::{random_deal, Rng};
+    use std::time::{Duration, Instant};
+    let mut rng = Rng::new(7);
+    let start = Instant::now();
+    for _ in 0..32 {
+        assert!(solve(&random_deal(&mut rng)).is_some());
+    }
+    assert!(start.elapsed() < Duration::from_secs(120));
+}
@@ -0,0 +1,142 @@
+use cards::{Card, Deal, Suit};
+use contract::{Seat, SEATS};
+
+// Gives up on constraints that are impossible, or nearly so, after this
+// many deals.
+const MAX_ATTEMPTS : u32 = 1_000_000;
+
+// SplitMix64: small, fast, and the same sequence on every platform, which
+// is all a seeded dealer needs.
+pub struct Rng {
+    state_ : u64,
+}
+
+impl Rng {
+    pub fn new(seed : u64) -> Rng {
+        Rng {
+            state_ : seed,
+        }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state_ = self.state_.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state_;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    // Uniform in 0..n, rejecting the values that would bias the low end.
+    pub fn below(&mut self, n : u64) -> u64 {
+        let zone = u64::MAX - u64::MAX % n;
+        loop {
+            let x = self.next_u64();
+            if x < zone {
+                return x % n;
+            }
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Constraint {
+    // High card points, inclusive.
+    Hcp(Seat, u32, u32),
+    Balanced(Seat),
+    // Number of cards in a suit, inclusive.
+    SuitLength(Seat, Suit, usize, usize),
+}
+
+impl Constraint {
+    pub fn matches(&self, deal : &Deal) -> bool {
+        match *self {
+            Constraint::Hcp(seat, min, max) => {
+                let hcp = deal.hand(seat).hcp();
+                min <= hcp && hcp <= max
+            },
+            Constraint::Balanced(seat) => deal.hand(seat).is_balanced(),
+            Constraint::SuitLength(seat, suit, min, max) => {
+                let length = deal.hand(seat).length(suit);
+                min <= length && length <= max
+            },
+        }
+    }
+}
+
+// Shuffles the pack and deals thirteen cards to each seat.
+pub fn random_deal(rng : &mut Rng) -> Deal {
+    let mut pack : Vec<Card> = (0..52).map(Card::from_index).collect();
+    // Fisher-Yates.
+    for i in (1..pack.len()).rev() {
+        let j = rng.below(i as u64 + 1) as usize;
+        pack.swap(i, j);
+    }
+    let mut deal = Deal::new();
+    for (&seat, cards) in SEATS.iter().zip(pack.chunks(13)) {
+        for &card in cards {
+            deal.hand_mut(seat).insert(card);
+        }
+    }
+    deal
+}
+
+// Deals boards one after another from a seed, so the same seed and
+// constraints always give the same set.
+pub struct DealGenerator {
+    rng_ : Rng,
+    constraints_ : Vec<Constraint>,
+}
+
+impl DealGenerator {
+    pub fn new(seed : u64) -> DealGenerator {
+        DealGenerator {
+            rng_ : Rng::new(seed),
+            constraints_ : Vec::new(),
+        }
+    }
+
+    pub fn add_constraint(&mut self, constraint : Constraint) {
+        self.constraints_.push(constraint);
+    }
+
+    // Redeals until every constraint is met. None if that never happens.
+    pub fn next_deal(&mut self) -> Option<Deal> {
+        for _ in 0..MAX_ATTEMPTS {
+            let deal = random_deal(&mut self.rng_);
+            if self.constraints_.iter().all(|c| c.matches(&deal)) {
+                return Some(deal);
+            }
+        }
+        None
+    }
+}
+
+#[test]
+fn test_seeded() {
+    let mut generator = DealGenerator::new(42);
+    let first : Vec<Deal> =
+        (0..3).map(|_| generator.next_deal().unwrap()).collect();
+    let mut generator = DealGenerator::new(42);
+    for deal in &first {
+        assert!(deal.validate().is_ok());
+        assert!(generator.next_deal().as_ref() == Some(deal));
+    }
+    assert!(first[0] != first[1]);
+    assert!(DealGenerator::new(43).next_deal().as_ref() != Some(&first[0]));
+}
+
+#[test]
+fn test_constraints() {
+    let mut generator = DealGenerator::new(7);
+    generator.add_constraint(Constraint::Hcp(Seat::North, 15, 17));
+    generator.add_constraint(Constraint::Balanced(Seat::North));
+    generator.add_constraint(
+        Constraint::SuitLength(Seat::East, Suit::Spades, 6, 13));
+    for _ in 0..5 {
+        let deal = generator.next_deal().unwrap();
+        let north = deal.hand(Seat::North);
+        assert!(north.hcp() >= 15 && north.hcp() <= 17);
+        assert!(north.is_balanced());
+        assert!(deal.hand(Seat::East).length(Suit::Spades) >= 6);
+    }
+}
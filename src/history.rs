@@ -0,0 +1,93 @@
+use contract::Seat;
+use data::Entry;
+
+// Every committed edit, so that each can be taken back and made again. The
+// edits hold what changed on both sides, so undoing one never depends on
+// replaying the ones before it.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Edit {
+    // Seat, and the player's name before and after.
+    Player(Seat, String, String),
+    // Entry index, and the entry before and after.
+    Change(usize, Entry, Entry),
+    // A new entry at the end.
+    Add(Entry),
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct History {
+    edits_ : Vec<Edit>,
+    // How many of the edits are in effect; the rest have been undone.
+    done_ : usize,
+}
+
+impl History {
+    pub fn new() -> History {
+        History {
+            edits_ : Vec::new(),
+            done_ : 0,
+        }
+    }
+
+    pub fn edits(&self) -> &[Edit] {
+        &self.edits_
+    }
+
+    pub fn undone(&self) -> usize {
+        self.edits_.len() - self.done_
+    }
+
+    // A new edit can't be redone past, so anything undone is forgotten.
+    pub fn push(&mut self, edit : Edit) {
+        self.edits_.truncate(self.done_);
+        self.edits_.push(edit);
+        self.done_ += 1;
+    }
+
+    // The edit to take back, if any.
+    pub fn undo(&mut self) -> Option<&Edit> {
+        if self.done_ == 0 {
+            return None;
+        }
+        self.done_ -= 1;
+        Some(&self.edits_[self.done_])
+    }
+
+    // The edit to make again, if any.
+    pub fn redo(&mut self) -> Option<&Edit> {
+        if self.done_ == self.edits_.len() {
+            return None;
+        }
+        self.done_ += 1;
+        Some(&self.edits_[self.done_ - 1])
+    }
+}
+
+impl Default for History {
+    fn default() -> History {
+        History::new()
+    }
+}
+
+#[cfg(test)]
+use data::Table;
+
+#[test]
+fn test_undo_redo() {
+    let mut history = History::new();
+    assert!(history.undo().is_none() && history.redo().is_none());
+    let entry = Entry::new(&Table::new(), Seat::North, 1);
+    history.push(Edit::Add(entry.clone()));
+    history.push(Edit::Player(Seat::East, String::from("East"),
+                              String::from("Ann")));
+    assert!(matches!(history.undo(), Some(&Edit::Player(Seat::East, _, _))));
+    assert!(history.undone() == 1);
+    assert!(matches!(history.redo(), Some(&Edit::Player(..))));
+    assert!(history.redo().is_none());
+    history.undo();
+    history.undo();
+    assert!(history.undo().is_none());
+    // A fresh edit drops the ones that were undone.
+    history.push(Edit::Add(entry));
+    assert!(history.edits().len() == 1 && history.undone() == 0);
+}
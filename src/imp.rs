@@ -0,0 +1,176 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use data::Entry;
+
+// Team A sits NS in the open room and EW in the closed room.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Room {
+    Open, Closed
+}
+
+impl fmt::Display for Room {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}",
+               match *self {
+                   Room::Open   => "A",
+                   Room::Closed => "B",
+               })
+    }
+}
+
+// Lower bounds of each step of the WBF IMP scale.
+const IMP_SCALE : [i32; 24] = [
+    20, 50, 90, 130, 170, 220, 270, 320, 370, 430, 500, 600,
+    750, 900, 1100, 1300, 1500, 1750, 2000, 2250, 2500, 3000, 3500, 4000,
+];
+
+pub fn imps(difference : i32) -> i32 {
+    let steps = IMP_SCALE.iter().filter(|&&b| difference.abs() >= b).count();
+    difference.signum() * steps as i32
+}
+
+// Positive IMPs go to team A.
+pub struct BoardImps {
+    pub board_num : u32,
+    pub open_ns_score : i32,
+    pub closed_ns_score : i32,
+    pub imps : i32,
+}
+
+pub struct MatchResult {
+    pub boards : Vec<BoardImps>,
+    // Boards that were only scored in one room so far.
+    pub unmatched : Vec<u32>,
+    // Boards with more than one entry in a room, which aren't scored until
+    // the extra entry is fixed.
+    pub conflicts : Vec<u32>,
+    pub team_a_imps : i32,
+    pub team_b_imps : i32,
+}
+
+impl MatchResult {
+    pub fn net(&self) -> i32 {
+        self.team_a_imps - self.team_b_imps
+    }
+}
+
+// Pairs each open-room entry with the closed-room entry for the same board.
+// Entries without a room or a score are left out.
+pub fn score_match(entries : &[Entry]) -> MatchResult {
+    let mut rooms : BTreeMap<u32, (Option<i32>, Option<i32>)> =
+        BTreeMap::new();
+    let mut conflicts = BTreeSet::new();
+    for entry in entries {
+        let (room, score) = match (entry.room(), entry.ns_score()) {
+            (Some(r), Some(s)) => (r, s),
+            _ => continue,
+        };
+        let board = rooms.entry(entry.board_num()).or_insert((None, None));
+        let slot = match room {
+            Room::Open   => &mut board.0,
+            Room::Closed => &mut board.1,
+        };
+        if slot.is_some() {
+            conflicts.insert(entry.board_num());
+        }
+        *slot = Some(score);
+    }
+
+    let mut result = MatchResult {
+        boards : Vec::new(),
+        unmatched : Vec::new(),
+        conflicts : conflicts.iter().cloned().collect(),
+        team_a_imps : 0,
+        team_b_imps : 0,
+    };
+    for (&board_num, scores) in &rooms {
+        if conflicts.contains(&board_num) {
+            continue;
+        }
+        let (open, closed) = match *scores {
+            (Some(o), Some(c)) => (o, c),
+            _ => { result.unmatched.push(board_num); continue; },
+        };
+        let board_imps = imps(open - closed);
+        if board_imps > 0 {
+            result.team_a_imps += board_imps;
+        } else {
+            result.team_b_imps -= board_imps;
+        }
+        result.boards.push(BoardImps {
+            board_num,
+            open_ns_score : open,
+            closed_ns_score : closed,
+            imps : board_imps,
+        });
+    }
+    result
+}
+
+#[test]
+fn test_imp_scale() {
+    assert!(imps(0) == 0);
+    assert!(imps(10) == 0);
+    assert!(imps(20) == 1);
+    assert!(imps(-420) == -9);
+    assert!(imps(430) == 10);
+    assert!(imps(1100) == 15);
+    assert!(imps(3990) == 23);
+    assert!(imps(-7600) == -24);
+}
+
+#[cfg(test)]
+use contract::Seat;
+#[cfg(test)]
+use data::Table;
+#[cfg(test)]
+use parse::parse_contract;
+
+#[cfg(test)]
+fn room_entry(room : Room, board : u32, declarer : Seat, contract : &str,
+              margin : i32) -> Entry {
+    let mut entry = Entry::new(&Table::new(), declarer, board);
+    entry.set_contract(parse_contract(contract).unwrap());
+    entry.record(margin);
+    entry.set_room(Some(room));
+    entry
+}
+
+#[test]
+fn test_score_match() {
+    let entries = vec![
+        room_entry(Room::Open, 1, Seat::North, "4S", 0),
+        room_entry(Room::Closed, 1, Seat::South, "2S", 2),
+        room_entry(Room::Open, 2, Seat::East, "3NT", 0),
+        room_entry(Room::Closed, 2, Seat::West, "3NT", 1),
+        room_entry(Room::Open, 3, Seat::North, "1NT", 0),
+    ];
+    let result = score_match(&entries);
+    assert!(result.boards.len() == 2);
+    assert!(result.unmatched == vec![3]);
+    // 420 against 170.
+    assert!(result.boards[0].imps == 6);
+    // Board 2 is NS vulnerable, so EW score 400 and 430.
+    assert!(result.boards[1].open_ns_score == -400);
+    assert!(result.boards[1].imps == 1);
+    assert!(result.team_a_imps == 7 && result.team_b_imps == 0);
+    assert!(result.net() == 7);
+    assert!(result.conflicts.is_empty());
+}
+
+#[test]
+fn test_score_match_conflict() {
+    let entries = vec![
+        room_entry(Room::Open, 1, Seat::North, "4S", 0),
+        room_entry(Room::Closed, 1, Seat::South, "2S", 2),
+        room_entry(Room::Open, 1, Seat::North, "4S", -1),
+        room_entry(Room::Open, 2, Seat::East, "3NT", 0),
+        room_entry(Room::Closed, 2, Seat::West, "3NT", 1),
+    ];
+    let result = score_match(&entries);
+    assert!(result.conflicts == vec![1]);
+    assert!(result.unmatched.is_empty());
+    assert!(result.boards.len() == 1);
+    assert!(result.boards[0].board_num == 2);
+}
@@ -1,33 +1,40 @@
 extern crate pancurses;
 
-use contract::{Seat, Contract, ContractDoubled};
+use auction::{Auction, Call};
+use cards::Deal;
+use chicago::{self, DEALS_PER_WHEEL};
+use contract::{Seat, Side, SEATS};
 use data::{Table, Entry};
-use parse::{parse_input, get_error_cursor};
-use score::Score;
+use dds::{self, DdTable};
+use history::{Edit, History};
+use parse::{parse_input, parse_honors, parse_room};
+use rubber::{RubberScorer, DealOutcome};
+use save::{save_session, SavedSession};
+use score::{possible_results, ScoringMode};
 use self::pancurses::{Input, Window};
 use std::mem;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
 
 const CURSOR_NORMAL     : i16 = 1;
 const CURSOR_ERROR      : i16 = 3;
-const CURSOR_DOUBLED    : i16 = 2;
-const CURSOR_VULNERABLE : i16 = 2;
-const CURSOR_REDOUBLED  : i16 = 4;
-const CURSOR_CONTRACT   : i16 = 5;
-const CURSOR_UNDERTRICK : i16 = 2;
 const CURSOR_ENTERING   : i16 = 6;
 
 #[derive(Copy, Clone, PartialEq, Eq)]
 enum EntryField {
     Number,
+    Declarer,
     Name,
+    Room,
     Vulnerability,
     Contract,
     Result,
+    Honors,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq)]
 enum Selection {
-    Unselected,
     NameSelect(Seat),
     // On an entry
     FieldSelect(usize, EntryField),
@@ -39,6 +46,16 @@ enum FieldStatus {
     Entering,
 }
 
+// One row of the entries pane.
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum EntryLine {
+    Entry(usize),
+    // Totals after the entry.
+    Summary(usize),
+    PartialNote,
+    Partial,
+}
+
 struct InputState {
     selection_ : Selection,
     entry_ : Option<String>,
@@ -89,9 +106,31 @@ pub struct Interface {
     // Contract-related
     table_: Table,
     dealer_ : Seat,
+    mode_ : ScoringMode,
     entries_ : Vec<Entry>,
+    history_ : History,
+    // Finished rubbers, keyed by the entry that ended them.
+    rubbers_ : Vec<(usize, RubberScorer)>,
+    rubber_ : RubberScorer,
+    save_path_ : Option<PathBuf>,
+    save_failed_ : bool,
     input_state_ : InputState,
+    // Why the last input was turned down; it stays open to be fixed.
+    input_error_ : Option<String>,
     partial_entry_ : Option<Entry>,
+    // The auction being bid for an entry, or for a new board when the index
+    // is one past the last entry.
+    auction_ : Option<(usize, Auction)>,
+    // Typing a board number to jump to.
+    jumping_ : bool,
+    // The first row of the entries pane in view.
+    entries_top_ : usize,
+    // Solving a deal takes a while, so it happens on another thread and
+    // tables turn up as they're done.
+    solver_ : Sender<Deal>,
+    solved_ : Receiver<(Deal, Option<DdTable>)>,
+    solving_ : Vec<Deal>,
+    dd_tables_ : Vec<(Deal, Option<DdTable>)>,
 
     root_window_ : Window,
     entry_window_ : Window,
@@ -106,6 +145,53 @@ pub struct Interface {
     entries_border_window_ : Window,
 }
 
+// Rows, columns, top row and left column of a pane.
+type Rect = (i32, i32, i32, i32);
+
+struct Layout {
+    entry : Rect,
+    table : Rect,
+    values : Rect,
+    entries : Rect,
+}
+
+// Wide enough to put the values beside the table.
+const SIDE_BY_SIDE_COLS : i32 = 70;
+const VALUES_COLS : i32 = 56;
+// An entry row and its border. Anything narrower would wrap each row onto
+// the next line, so it's the least width that works.
+const ENTRIES_COLS : i32 = 56;
+
+// The input line and the table go in the top left corner. The values go
+// beside the table when the terminal is wide enough and below it when it
+// isn't, and the entries take the rows left at the bottom. None when there
+// isn't room for all of that.
+fn layout(rows : i32, cols : i32) -> Option<Layout> {
+    let (values, entries_top) = if cols >= SIDE_BY_SIDE_COLS {
+        ((10, (cols - 14).min(VALUES_COLS), 0, 14), 10)
+    } else {
+        ((10, VALUES_COLS, 10, 0), 20)
+    };
+    if cols < ENTRIES_COLS || rows < entries_top + 3 {
+        return None;
+    }
+    Some(Layout {
+        entry : (1, 14, 0, 0),
+        table : (9, 15, 1, 0),
+        values,
+        entries : (rows - entries_top, ENTRIES_COLS, entries_top, 0),
+    })
+}
+
+fn place(root : &Window, (rows, cols, y, x) : Rect) -> Window {
+    root.derwin(rows, cols, y, x).unwrap()
+}
+
+// Windows aren't freed when they're dropped.
+fn replace_window(slot : &mut Window, window : Window) {
+    mem::replace(slot, window).delwin();
+}
+
 fn center_pad(window : &Window, text : &str, y : i32) -> i32 {
     window.mvaddstr(y, (window.get_max_x() - text.len() as i32) / 2, text)
 }
@@ -126,22 +212,6 @@ fn border(window : &Window) {
     window.border('|','|','-','-','+','+','+','+');
 }
 
-fn draw_input(input : &str, window : Window, y : i32, x : i32) {
-    let (parse_err, contract) = match parse_input(&input) {
-        Ok(c)  => (None,    Some(c)),
-        Err(e) => (Some(e), None),
-    };
-    let error_loc = get_error_cursor(parse_err);
-    window.mv(y, x);
-    window.color_set(CURSOR_NORMAL);
-    for (i, c) in input.chars().enumerate() {
-        if Some(i) == error_loc {
-            window.color_set(CURSOR_ERROR);
-        }
-        window.addch(c);
-    }
-}
-
 fn set_field_cursor(window : &Window, is_selected : FieldStatus) {
     window.attroff(pancurses::A_UNDERLINE);
     window.color_set(CURSOR_NORMAL);
@@ -152,31 +222,111 @@ fn set_field_cursor(window : &Window, is_selected : FieldStatus) {
     };
 }
 
-fn record(entry : &mut Entry, f : EntryField, input : String) {
+// Bids are shown without the T of NT to fit four seats in the values pane.
+fn call_label(call : Call) -> String {
+    call.to_string().replace("NT", "N")
+}
+
+fn seat_column(seat : Seat) -> usize {
+    SEATS.iter().position(|&s| s == seat).unwrap()
+}
+
+// Input that doesn't fit the field leaves the entry alone and says why.
+fn record(entry : &mut Entry, table : &Table, f : EntryField, input : &str)
+    -> Result<(), String> {
     match f {
-        EntryField::Name          => entry.set_name(input),
-        EntryField::Result        =>
-            { input.parse().map(|r| entry.record(r)); },
+        EntryField::Number        => {
+            let n = input.trim().parse().unwrap_or(0);
+            if !entry.set_board_num(n) {
+                return Err(format!("Invalid board: {}", input));
+            }
+        },
+        EntryField::Declarer      =>
+            entry.set_declarer(table, input.trim().to_uppercase().parse()?),
+        EntryField::Name          => entry.set_name(String::from(input)),
+        // Only an empty room clears it.
+        EntryField::Room          => {
+            let room = parse_room(input);
+            if room.is_none() && !input.trim().is_empty() {
+                return Err(format!("Invalid room: {}", input));
+            }
+            entry.set_room(room)
+        },
+        EntryField::Result        => {
+            let r = input.trim().parse()
+                .map_err(|_| format!("Invalid result: {}", input))?;
+            entry.record(r)
+        },
         EntryField::Contract      =>
-            { parse_input(&input).map(|c| entry.set_contract(c)); },
+            entry.set_contract(parse_input(input)
+                               .map_err(|e| e.to_string())?),
         EntryField::Vulnerability =>
             entry.set_vulnerable(input.to_lowercase() == "v"),
-        _ => (),
+        EntryField::Honors        => {
+            let h = parse_honors(input).map_err(|e| e.to_string())?;
+            entry.set_honors(h).map_err(|e| e.to_string())?
+        },
     }
+    Ok(())
+}
+
+// An entry from the history, brought into the session's mode. Only a mode
+// switch since the edit resets its vulnerability.
+fn in_mode(mut entry : Entry, mode : ScoringMode) -> Entry {
+    if entry.mode() != mode {
+        entry.set_mode(mode);
+    }
+    entry
+}
+
+fn spawn_solver() -> (Sender<Deal>, Receiver<(Deal, Option<DdTable>)>) {
+    let (deal_tx, deal_rx) = mpsc::channel::<Deal>();
+    let (table_tx, table_rx) = mpsc::channel();
+    thread::spawn(move || {
+        for deal in deal_rx {
+            if table_tx.send((deal, dds::solve(&deal))).is_err() {
+                break;
+            }
+        }
+    });
+    (deal_tx, table_rx)
 }
 
 impl Interface {
-    pub fn new(root_window : Window) -> Interface {
-        let tablewin = root_window.derwin(9, 15, 1, 0).unwrap();
-        let valueswin = root_window.derwin(10, 15, 0, 14).unwrap();
-        let entrywin = root_window.derwin(1, 14, 0, 0).unwrap();
-        let entrieswin = root_window.derwin(40, 40, 10, 0).unwrap();
-        Interface {
+    // Fails when the terminal is too small to lay out.
+    pub fn new(root_window : Window) -> Result<Interface, String> {
+        let (rows, cols) = root_window.get_max_yx();
+        let layout = match layout(rows, cols) {
+            Some(l) => l,
+            None    =>
+                return Err(format!("Terminal too small: {} columns by {} \
+                                    rows", cols, rows)),
+        };
+        let tablewin = place(&root_window, layout.table);
+        let valueswin = place(&root_window, layout.values);
+        let entrywin = place(&root_window, layout.entry);
+        let entrieswin = place(&root_window, layout.entries);
+        let (solver, solved) = spawn_solver();
+        Ok(Interface {
             table_ : Table::new(),
             dealer_ : Seat::North,
+            mode_ : ScoringMode::Duplicate,
             entries_ : Vec::new(),
+            history_ : History::new(),
+            rubbers_ : Vec::new(),
+            rubber_ : RubberScorer::new(),
+            save_path_ : None,
+            save_failed_ : false,
             input_state_ : InputState::new(),
+            input_error_ : None,
             partial_entry_ : None,
+            auction_ : None,
+            jumping_ : false,
+            entries_top_ : 0,
+            solver_ : solver,
+            solved_ : solved,
+            solving_ : Vec::new(),
+            dd_tables_ : Vec::new(),
             root_window_ : root_window,
             entry_window_ : entrywin,
             table_window_ : shrink(&tablewin),
@@ -185,6 +335,55 @@ impl Interface {
             values_border_window_ : valueswin,
             entries_window_ : shrink(&entrieswin),
             entries_border_window_ : entrieswin,
+        })
+    }
+
+    // Puts every pane back in place for the terminal's new size, leaving
+    // everything as it was when it's too small.
+    fn relayout(&mut self) {
+        let (rows, cols) = self.root_window_.get_max_yx();
+        let layout = match layout(rows, cols) {
+            Some(l) => l,
+            None    => return,
+        };
+        let root = &self.root_window_;
+        let table = place(root, layout.table);
+        let values = place(root, layout.values);
+        let entries = place(root, layout.entries);
+        // Inner windows go before the borders they were made from.
+        replace_window(&mut self.entry_window_, place(root, layout.entry));
+        replace_window(&mut self.table_window_, shrink(&table));
+        replace_window(&mut self.values_window_, shrink(&values));
+        replace_window(&mut self.entries_window_, shrink(&entries));
+        replace_window(&mut self.table_border_window_, table);
+        replace_window(&mut self.values_border_window_, values);
+        replace_window(&mut self.entries_border_window_, entries);
+        root.clear();
+        self.follow_selection();
+    }
+
+    // Every committed change is written here from now on.
+    pub fn set_save_path(&mut self, path : PathBuf) {
+        self.save_path_ = Some(path);
+    }
+
+    pub fn restore(&mut self, saved : SavedSession) {
+        self.table_ = saved.table;
+        self.mode_ = saved.mode;
+        self.dealer_ = saved.dealer;
+        self.entries_ = saved.entries;
+        self.history_ = saved.history;
+        self.partial_entry_ = None;
+        self.rescore();
+        self.analyze();
+    }
+
+    fn autosave(&mut self) {
+        if let Some(ref path) = self.save_path_ {
+            self.save_failed_ = save_session(path, &self.table_, self.mode_,
+                                             self.dealer_, &self.entries_,
+                                             &self.history_)
+                .is_err();
         }
     }
 
@@ -197,24 +396,51 @@ impl Interface {
             pancurses::curs_set(0);
         }
         self.draw_entries();
+        self.draw_values();
         self.draw_input();
         self.refresh();
     }
 
     pub fn get_input(&mut self) -> bool {
+        // Wake up now and then to show tables as they're solved.
+        let delay = if self.solving_.is_empty() { -1 } else { 500 };
+        self.root_window_.timeout(delay);
         let c = match self.root_window_.getch() {
             Some(Input::Character(cc)) => cc,
             Some(Input::KeyBackspace) => '\x7f',
+            Some(Input::KeyResize) => {
+                self.relayout();
+                return true;
+            },
+            Some(Input::KeyPPage) => {
+                self.page(false);
+                return true;
+            },
+            Some(Input::KeyNPage) => {
+                self.page(true);
+                return true;
+            },
+            None if !self.solving_.is_empty() => {
+                self.analyze();
+                return true;
+            },
             None => { return false; },
             _    => { return true; },
         };
-        match c {
-            '\t' => self.cycle_input(),
-            '\n' => self.enter_input(),
-            _    => self.input_char(c),
-        };
+        if self.auction_.is_some() {
+            self.auction_char(c);
+        } else {
+            match c {
+                '\t' => self.cycle_input(),
+                '\n' => self.enter_input(),
+                _    => self.input_char(c),
+            };
+        }
         self.update_entries();
-        return true;
+        self.rescore();
+        self.analyze();
+        self.follow_selection();
+        true
     }
 
     fn navigate(&mut self, c : char) {
@@ -224,13 +450,21 @@ impl Interface {
             'k' => self.move_up(),
             'l' => self.move_right(),
             ' ' => self.activate(),
+            'm' => self.cycle_mode(),
+            'a' => self.start_auction(),
+            'g' => {
+                self.jumping_ = true;
+                self.input_state_.entry_ = Some(String::new());
+            },
+            'u' => self.undo(),
+            // Ctrl-R
+            '\x12' => self.redo(),
             _   => (),
         }
     }
 
     fn cycle_input(&mut self) {
         let new_selection = match self.input_state_.selection_ {
-            Selection::Unselected        => Selection::NameSelect(Seat::North),
             Selection::NameSelect(_)     =>
                 Selection::FieldSelect(0, EntryField::Contract),
             Selection::FieldSelect(_, _) => Selection::NameSelect(Seat::North),
@@ -238,6 +472,194 @@ impl Interface {
 
         self.input_state_.selection_ = new_selection;
         self.input_state_.entry_ = None;
+        self.input_error_ = None;
+        self.jumping_ = false;
+    }
+
+    // From the table this bids a new board, from an entry it rebids that one.
+    fn start_auction(&mut self) {
+        let (ix, dealer) = match self.input_state_.selection_ {
+            Selection::FieldSelect(ix, _) if ix < self.entries_.len() => {
+                let board = self.entries_[ix].board_num();
+                (ix, self.mode_.dealer(board))
+            },
+            _ => (self.entries_.len(), self.dealer_),
+        };
+        self.auction_ = Some((ix, Auction::new(dealer)));
+        self.input_state_.entry_ = Some(String::new());
+    }
+
+    // Enter makes the typed call, backspace on an empty call takes back the
+    // last one, and tab abandons the auction.
+    fn auction_char(&mut self, c : char) {
+        let input = self.input_state_.entry_.get_or_insert_with(String::new);
+        let auction = &mut self.auction_.as_mut().unwrap().1;
+        match c {
+            '\t' => {
+                self.auction_ = None;
+                self.input_state_.entry_ = None;
+                return;
+            },
+            '\n' => match input.parse() {
+                Ok(call) if auction.call(call).is_ok() => input.clear(),
+                _ => (),
+            },
+            '\x7f' if input.is_empty() => { auction.undo(); },
+            '\x7f' => { input.pop(); },
+            _ => input.push(c),
+        }
+        if auction.is_finished() {
+            self.finish_auction();
+        }
+    }
+
+    // A passed-out auction leaves everything as it was.
+    fn finish_auction(&mut self) {
+        let (ix, auction) = self.auction_.take().unwrap();
+        self.input_state_.entry_ = None;
+        let (contract, declarer) = match auction.result() {
+            Some(r) => r,
+            None    => return,
+        };
+        let ix = if ix == self.entries_.len() {
+            let mut entry = Entry::with_mode(&self.table_, declarer,
+                                             ix as u32 + 1, self.mode_);
+            entry.set_contract(contract);
+            self.push_entry(entry)
+        } else {
+            let before = self.entries_[ix].clone();
+            self.entries_[ix].set_declarer(&self.table_, declarer);
+            self.entries_[ix].set_contract(contract);
+            self.commit_change(ix, before);
+            ix
+        };
+        self.input_state_.selection_ =
+            Selection::FieldSelect(ix, EntryField::Result);
+        self.rescore();
+        self.autosave();
+    }
+
+    // Keeps the edit that turned `before` into entry `ix` as it is now.
+    fn commit_change(&mut self, ix : usize, before : Entry) {
+        if self.entries_[ix] != before {
+            let after = self.entries_[ix].clone();
+            self.history_.push(Edit::Change(ix, before, after));
+        }
+    }
+
+    fn undo(&mut self) {
+        if let Some(edit) = self.history_.undo().cloned() {
+            self.apply(edit, false);
+        }
+    }
+
+    fn redo(&mut self) {
+        if let Some(edit) = self.history_.redo().cloned() {
+            self.apply(edit, true);
+        }
+    }
+
+    // Makes `edit` again, or takes it back when not `forward`. Entries keep
+    // whatever mode the session is in now.
+    fn apply(&mut self, edit : Edit, forward : bool) {
+        match edit {
+            Edit::Player(seat, before, after) =>
+                self.table_.set_player(seat,
+                                       if forward { &after } else { &before }),
+            Edit::Change(ix, before, after) => {
+                let entry = if forward { after } else { before };
+                if let Some(e) = self.entries_.get_mut(ix) {
+                    *e = in_mode(entry, self.mode_);
+                }
+            },
+            Edit::Add(entry) => {
+                if forward {
+                    self.entries_.push(in_mode(entry, self.mode_));
+                } else {
+                    self.entries_.pop();
+                }
+                // The next board has moved.
+                self.partial_entry_ = None;
+            },
+        }
+        if let Selection::FieldSelect(ix, f) = self.input_state_.selection_ {
+            if ix > self.entries_.len() {
+                self.input_state_.selection_ =
+                    Selection::FieldSelect(self.entries_.len(), f);
+            }
+        }
+        self.rescore();
+        self.autosave();
+    }
+
+    fn cycle_mode(&mut self) {
+        self.mode_ = self.mode_.next();
+        for entry in self.entries_.iter_mut() {
+            entry.set_mode(self.mode_);
+        }
+        if let Some(ref mut e) = self.partial_entry_ {
+            e.set_mode(self.mode_);
+        }
+        self.rescore();
+        self.autosave();
+    }
+
+    // Brings the dealer and any rubber ledger up to date with the entries.
+    fn rescore(&mut self) {
+        let next_board = self.entries_.len() as u32 + 1;
+        self.dealer_ = self.mode_.dealer(next_board);
+        self.rubbers_.clear();
+        self.rubber_ = RubberScorer::new();
+        if self.mode_ != ScoringMode::Rubber {
+            return;
+        }
+        for (ix, entry) in self.entries_.iter_mut().enumerate() {
+            entry.set_vulnerability(
+                self.rubber_.is_vulnerable(Side::NorthSouth),
+                self.rubber_.is_vulnerable(Side::EastWest));
+            let outcome = match (entry.declarer(), entry.contract(),
+                                 entry.result()) {
+                (Some(d), Some(c), Some(r)) =>
+                    self.rubber_.record_with_honors(d.side(), &c, r,
+                                                    entry.honors()),
+                _ => DealOutcome::Continues,
+            };
+            if let DealOutcome::Rubber(_) = outcome {
+                let finished = mem::take(&mut self.rubber_);
+                self.rubbers_.push((ix, finished));
+            }
+        }
+        if let Some(ref mut e) = self.partial_entry_ {
+            e.set_vulnerability(
+                self.rubber_.is_vulnerable(Side::NorthSouth),
+                self.rubber_.is_vulnerable(Side::EastWest));
+        }
+    }
+
+    // Picks up finished tables and sends off any deals not yet solved.
+    fn analyze(&mut self) {
+        while let Ok((deal, table)) = self.solved_.try_recv() {
+            self.solving_.retain(|&d| d != deal);
+            self.dd_tables_.push((deal, table));
+        }
+        let deals : Vec<Deal> = self.entries_.iter()
+            .chain(self.partial_entry_.iter())
+            .filter_map(|e| e.deal().cloned())
+            .collect();
+        for deal in deals {
+            if self.solving_.contains(&deal) ||
+                self.dd_tables_.iter().any(|t| t.0 == deal) {
+                continue;
+            }
+            if self.solver_.send(deal).is_ok() {
+                self.solving_.push(deal);
+            }
+        }
+    }
+
+    fn dd_table(&self, deal : &Deal) -> Option<&DdTable> {
+        self.dd_tables_.iter().find(|t| t.0 == *deal)
+            .and_then(|t| t.1.as_ref())
     }
 
     fn update_entries(&mut self) {
@@ -250,41 +672,56 @@ impl Interface {
             panic!("Something has gone wrong.");
         }
 
-        if entryix == self.entries_.len() {
-            if self.partial_entry_.is_none() {
-                self.partial_entry_ =
-                    Some(Entry::new(&self.table_, Seat::North, 1));
-            }
+        if entryix == self.entries_.len() && self.partial_entry_.is_none() {
+            let next_board = self.entries_.len() as u32 + 1;
+            self.partial_entry_ = Some(Entry::with_mode(&self.table_,
+                                                        self.dealer_,
+                                                        next_board,
+                                                        self.mode_));
         }
     }
 
     fn move_left(&mut self) {
         self.input_state_.selection_ = match self.input_state_.selection_ {
             Selection::NameSelect(_) => Selection::NameSelect(Seat::West),
+            Selection::FieldSelect(x, EntryField::Number) =>
+                Selection::FieldSelect(x, EntryField::Number),
+            Selection::FieldSelect(x, EntryField::Declarer) =>
+                Selection::FieldSelect(x, EntryField::Number),
             Selection::FieldSelect(x, EntryField::Name) =>
+                Selection::FieldSelect(x, EntryField::Declarer),
+            Selection::FieldSelect(x, EntryField::Room) =>
                 Selection::FieldSelect(x, EntryField::Name),
             Selection::FieldSelect(x, EntryField::Vulnerability) =>
-                Selection::FieldSelect(x, EntryField::Name),
+                Selection::FieldSelect(x, EntryField::Room),
             Selection::FieldSelect(x, EntryField::Contract) =>
                 Selection::FieldSelect(x, EntryField::Vulnerability),
             Selection::FieldSelect(x, EntryField::Result) =>
                 Selection::FieldSelect(x, EntryField::Contract),
-            _ => self.input_state_.selection_
+            Selection::FieldSelect(x, EntryField::Honors) =>
+                Selection::FieldSelect(x, EntryField::Result),
         }
     }
 
     fn move_right(&mut self) {
         self.input_state_.selection_ = match self.input_state_.selection_ {
             Selection::NameSelect(_) => Selection::NameSelect(Seat::East),
+            Selection::FieldSelect(x, EntryField::Number) =>
+                Selection::FieldSelect(x, EntryField::Declarer),
+            Selection::FieldSelect(x, EntryField::Declarer) =>
+                Selection::FieldSelect(x, EntryField::Name),
             Selection::FieldSelect(x, EntryField::Name) =>
+                Selection::FieldSelect(x, EntryField::Room),
+            Selection::FieldSelect(x, EntryField::Room) =>
                 Selection::FieldSelect(x, EntryField::Vulnerability),
             Selection::FieldSelect(x, EntryField::Vulnerability) =>
                 Selection::FieldSelect(x, EntryField::Contract),
             Selection::FieldSelect(x, EntryField::Contract) =>
                 Selection::FieldSelect(x, EntryField::Result),
             Selection::FieldSelect(x, EntryField::Result) =>
-                Selection::FieldSelect(x, EntryField::Result),
-            _ => self.input_state_.selection_,
+                Selection::FieldSelect(x, EntryField::Honors),
+            Selection::FieldSelect(x, EntryField::Honors) =>
+                Selection::FieldSelect(x, EntryField::Honors),
         }
     }
 
@@ -293,7 +730,6 @@ impl Interface {
             Selection::NameSelect(_)     => Selection::NameSelect(Seat::North),
             Selection::FieldSelect(0, y) => Selection::FieldSelect(0, y),
             Selection::FieldSelect(x, y) => Selection::FieldSelect(x - 1, y),
-            _ => self.input_state_.selection_,
         }
     }
 
@@ -302,7 +738,85 @@ impl Interface {
             Selection::NameSelect(_)     => Selection::NameSelect(Seat::South),
             Selection::FieldSelect(x, y) => Selection::FieldSelect(
                 if x == self.entries_.len() { x } else { x + 1}, y),
-            _ => self.input_state_.selection_,
+        }
+    }
+
+    // Moves the selection a pane's height up or down the entries. Like the
+    // other movement keys it waits until nothing is being typed.
+    fn page(&mut self, down : bool) {
+        if self.input_state_.entry_.is_some() {
+            return;
+        }
+        let rows = self.entries_window_.get_max_y().max(1) as usize;
+        if let Selection::FieldSelect(x, y) = self.input_state_.selection_ {
+            let x = if down {
+                (x + rows).min(self.entries_.len())
+            } else {
+                x.saturating_sub(rows)
+            };
+            self.input_state_.selection_ = Selection::FieldSelect(x, y);
+        }
+        self.update_entries();
+        self.follow_selection();
+    }
+
+    // Selects the first entry for the board, or the next board to enter.
+    fn jump(&mut self, input : &str) {
+        let board : u32 = match input.trim().parse() {
+            Ok(b) => b,
+            Err(_) => return,
+        };
+        let field = match self.input_state_.selection_ {
+            Selection::FieldSelect(_, f) => f,
+            _ => EntryField::Contract,
+        };
+        let ix = match self.entries_.iter()
+            .position(|e| e.board_num() == board) {
+            Some(ix) => ix,
+            None if board as usize == self.entries_.len() + 1 =>
+                self.entries_.len(),
+            None => return,
+        };
+        self.input_state_.selection_ = Selection::FieldSelect(ix, field);
+    }
+
+    fn entry_lines(&self) -> Vec<EntryLine> {
+        let mut lines = Vec::new();
+        for ix in 0..self.entries_.len() {
+            lines.push(EntryLine::Entry(ix));
+            if self.summary(ix).is_some() {
+                lines.push(EntryLine::Summary(ix));
+            }
+        }
+        if self.partial_entry_.is_some() {
+            lines.push(EntryLine::PartialNote);
+            lines.push(EntryLine::Partial);
+        }
+        lines
+    }
+
+    // Scrolls the entries pane just far enough to show the selected entry.
+    fn follow_selection(&mut self) {
+        let lines = self.entry_lines();
+        self.entries_top_ = self.entries_top_.min(lines.len());
+        let ix = match self.input_state_.selection_ {
+            Selection::FieldSelect(ix, _) => ix,
+            _ => return,
+        };
+        let target = if ix < self.entries_.len() {
+            EntryLine::Entry(ix)
+        } else {
+            EntryLine::Partial
+        };
+        let line = match lines.iter().position(|&l| l == target) {
+            Some(line) => line,
+            None       => return,
+        };
+        let rows = self.entries_window_.get_max_y().max(1) as usize;
+        if line < self.entries_top_ {
+            self.entries_top_ = line;
+        } else if line >= self.entries_top_ + rows {
+            self.entries_top_ = line + 1 - rows;
         }
     }
 
@@ -310,25 +824,35 @@ impl Interface {
         self.input_state_.selection_ = match self.input_state_.selection_ {
             Selection::NameSelect(s) =>
                 Selection::FieldSelect(self.add(s), EntryField::Contract),
-            _ => self.input_state_.selection_
+            _ => self.input_state_.selection_,
         };
     }
 
     fn add(&mut self, s : Seat) -> usize {
         let new_board = self.entries_.len() as u32 + 1;
-        self.entries_.push(Entry::new(&self.table_, s, new_board));
+        let entry = Entry::with_mode(&self.table_, s, new_board, self.mode_);
+        self.push_entry(entry)
+    }
+
+    fn push_entry(&mut self, entry : Entry) -> usize {
+        self.history_.push(Edit::Add(entry.clone()));
+        self.entries_.push(entry);
+        self.rescore();
+        self.autosave();
         self.entries_.len() - 1
     }
 
     fn enter_input(&mut self) {
-        match self.input_state_.selection_ {
-            Selection::Unselected => { return; },
-            _ => (),
-        };
+        if self.jumping_ {
+            let input = self.input_state_.entry_.take().unwrap_or_default();
+            self.jumping_ = false;
+            self.jump(&input);
+            return;
+        }
         match self.input_state_.entry_ {
             None => self.input_state_.entry_ = Some("".to_string()),
             Some(_) => {
-                let input = mem::replace(&mut self.input_state_.entry_, None);
+                let input = self.input_state_.entry_.take();
                 self.process_input(input.unwrap());
             },
         };
@@ -336,18 +860,43 @@ impl Interface {
 
     fn process_input(&mut self, input : String) {
         match self.input_state_.selection_ {
-            Selection::Unselected => panic!("How did you do that"),
-            Selection::NameSelect(s) => self.table_.set_player(s, &input),
-            Selection::FieldSelect(x, y) =>
-            { self.entry_mut(x).map(|e| record(e, y, input)); },
+            Selection::NameSelect(s) => {
+                let before = String::from(self.table_.get_player(s));
+                self.table_.set_player(s, &input);
+                if before != input {
+                    self.history_.push(Edit::Player(s, before, input));
+                }
+            },
+            // Only committed entries have a history.
+            Selection::FieldSelect(x, y) if x < self.entries_.len() => {
+                let before = self.entries_[x].clone();
+                match record(&mut self.entries_[x], &self.table_, y, &input) {
+                    Ok(())   => self.commit_change(x, before),
+                    Err(e)   => self.reject_input(input, e),
+                }
+            },
+            Selection::FieldSelect(_, y) =>
+                if let Some(ref mut e) = self.partial_entry_ {
+                    if let Err(e) = record(e, &self.table_, y, &input) {
+                        self.reject_input(input, e);
+                    }
+                },
         }
+        self.rescore();
+        self.autosave();
+    }
+
+    fn reject_input(&mut self, input : String, error : String) {
+        self.input_state_.entry_ = Some(input);
+        self.input_error_ = Some(error);
     }
 
     fn input_char(&mut self, c : char) {
-        if !self.input_state_.entry_.is_some() {
+        if self.input_state_.entry_.is_none() {
             self.navigate(c);
             return;
         }
+        self.input_error_ = None;
         match c {
             '\x7f' => {self.input_state_.entry_.as_mut().unwrap().pop();},
             _      => self.input_state_.entry_.as_mut().unwrap().push(c),
@@ -372,14 +921,7 @@ impl Interface {
 
     fn input(&self) -> &str {
         // Awkward.
-        self.input_state_.entry_.as_ref().map(String::as_str).unwrap_or("")
-    }
-
-    fn entry_mut(&mut self, ix : usize) -> Option<&mut Entry> {
-        if ix == self.entries_.len() {
-            return self.partial_entry_.as_mut();
-        }
-        self.entries_.get_mut(ix)
+        self.input_state_.entry_.as_deref().unwrap_or("")
     }
 
     fn draw_borders(&self) {
@@ -395,45 +937,112 @@ impl Interface {
         let midpoint_offset =
             if ew_length as i32 >= table_win.get_max_x() { 1 } else { 0 };
 
-        &table_win.clear();
-        set_field_cursor(&table_win, FieldStatus::NotSelected);
+        table_win.clear();
+        set_field_cursor(table_win, FieldStatus::NotSelected);
         table_win.mvaddch(midpoint, 0, '^');
         table_win.mvaddch(midpoint, table_win.get_max_x() - 1, 'v');
 
-        set_field_cursor(&table_win, self.input_state_.is_north());
-        center_pad(&table_win, self.north(), 0);
+        set_field_cursor(table_win, self.input_state_.is_north());
+        center_pad(table_win, self.north(), 0);
+
+        set_field_cursor(table_win, self.input_state_.is_south());
+        center_pad(table_win, self.south(), table_win.get_max_y() - 1);
 
-        set_field_cursor(&table_win, self.input_state_.is_south());
-        center_pad(&table_win, self.south(), table_win.get_max_y() - 1);
+        set_field_cursor(table_win, self.input_state_.is_east());
+        right_justify(table_win, self.east(), midpoint + midpoint_offset);
 
-        set_field_cursor(&table_win, self.input_state_.is_east());
-        right_justify(&table_win, self.east(), midpoint + midpoint_offset);
+        set_field_cursor(table_win, self.input_state_.is_west());
+        left_justify(table_win, self.west(), midpoint - midpoint_offset);
 
-        set_field_cursor(&table_win, self.input_state_.is_west());
-        left_justify(&table_win, self.west(), midpoint - midpoint_offset);
+        set_field_cursor(table_win, FieldStatus::NotSelected);
+        center_pad(table_win, &format!("Dealer: {}", self.dealer_), 1);
+        let status = if self.save_failed_ {
+            String::from("Save failed")
+        } else {
+            self.mode_.to_string()
+        };
+        center_pad(table_win, &status, table_win.get_max_y() - 2);
     }
 
     fn draw_entries(&self) {
         self.entries_window_.clear();
         self.entries_window_.mv(0, 0);
         set_field_cursor(&self.entry_window_, FieldStatus::NotSelected);
-        let ref entries : &Vec<_> = &self.entries_;
-        for (ix, entry) in entries.iter().enumerate() {
-            self.draw_entry(entry, ix);
-        };
-        if let Some(ref e) = self.partial_entry_ {
-            self.entries_window_.addstr("Partial entry detected\n");
-            self.draw_entry(e, self.entries_.len());
+        let rows = self.entries_window_.get_max_y().max(0) as usize;
+        for line in self.entry_lines().into_iter()
+            .skip(self.entries_top_).take(rows) {
+            match line {
+                EntryLine::Entry(ix)   =>
+                    self.draw_entry(&self.entries_[ix], ix),
+                EntryLine::Summary(ix) => self.draw_summary(ix),
+                EntryLine::PartialNote => {
+                    set_field_cursor(&self.entries_window_,
+                                     FieldStatus::NotSelected);
+                    self.entries_window_.addstr("Partial entry detected\n");
+                },
+                EntryLine::Partial     => {
+                    if let Some(ref e) = self.partial_entry_ {
+                        self.draw_entry(e, self.entries_.len());
+                    }
+                },
+            }
+        }
+    }
+
+    // Chicago gets a line per wheel of four deals, rubber a line per rubber.
+    fn summary(&self, ix : usize) -> Option<(String, (i32, i32))> {
+        match self.mode_ {
+            ScoringMode::Chicago
+                if (ix + 1).is_multiple_of(DEALS_PER_WHEEL as usize) => {
+                let start = ix + 1 - DEALS_PER_WHEEL as usize;
+                Some((format!("Wheel {}", chicago::wheel(ix as u32 + 1)),
+                      chicago::wheel_totals(&self.entries_[start..ix + 1])))
+            },
+            ScoringMode::Rubber => {
+                self.rubbers_.iter().find(|r| r.0 == ix).map(|(_, r)| {
+                    (String::from("Rubber"),
+                     (r.total(Side::NorthSouth), r.total(Side::EastWest)))
+                })
+            },
+            _ => None,
+        }
+    }
+
+    fn draw_summary(&self, ix : usize) {
+        let (label, (ns, ew)) = match self.summary(ix) {
+            Some(s) => s,
+            None    => return,
         };
+        set_field_cursor(&self.entries_window_, FieldStatus::NotSelected);
+        self.entries_window_.addstr(
+            &format!("{:<34}|{:5}|{:5}|\n", label, ns, ew));
     }
 
     fn draw_entry(&self, entry : &Entry, ix : usize) {
         let entry_win = &self.entries_window_;
         let input = &self.input_state_;
+        set_field_cursor(entry_win, input.is_field(ix, EntryField::Number));
+        entry_win.addstr(&format!("{:>3}", entry.board_num()));
+        set_field_cursor(entry_win, FieldStatus::NotSelected);
+        entry_win.addch('|');
+        set_field_cursor(entry_win, input.is_field(ix, EntryField::Declarer));
+        match entry.declarer() {
+            Some(d) => entry_win.addstr(&d.to_string()),
+            None    => entry_win.addstr(" "),
+        };
+        set_field_cursor(entry_win, FieldStatus::NotSelected);
+        entry_win.addch('|');
         set_field_cursor(entry_win, input.is_field(ix, EntryField::Name));
         entry_win.addstr(&format!("{:<10}", entry.name()));
         set_field_cursor(entry_win, FieldStatus::NotSelected);
         entry_win.addch('|');
+        set_field_cursor(entry_win, input.is_field(ix, EntryField::Room));
+        match entry.room() {
+            Some(r) => entry_win.addstr(&r.to_string()),
+            None    => entry_win.addstr(" "),
+        };
+        set_field_cursor(entry_win, FieldStatus::NotSelected);
+        entry_win.addch('|');
         set_field_cursor(entry_win,
                          input.is_field(ix, EntryField::Vulnerability));
         entry_win.addch(if entry.is_vulnerable() { 'V' } else { ' ' });
@@ -453,6 +1062,13 @@ impl Interface {
         };
         set_field_cursor(entry_win, FieldStatus::NotSelected);
         entry_win.addch('|');
+        set_field_cursor(entry_win, input.is_field(ix, EntryField::Honors));
+        match entry.honors() {
+            Some(h) => entry_win.addstr(&format!("{:>2}", h.to_string())),
+            None    => entry_win.addstr("  "),
+        };
+        set_field_cursor(entry_win, FieldStatus::NotSelected);
+        entry_win.addch('|');
         match entry.value() {
             Some(v) =>
                 match entry.declarer() {
@@ -465,20 +1081,160 @@ impl Interface {
                 None => entry_win.addstr("     |     ")
         };
         entry_win.addch('|');
+        // Par from North-South's point of view, to set against the score.
+        match (entry.deal(), entry.deal().and_then(|d| self.dd_table(d))) {
+            (_, Some(t)) =>
+                entry_win.addstr(&format!("{:+5}", entry.par(t).score)),
+            (Some(d), None) if self.solving_.contains(d) =>
+                entry_win.addstr("  ..."),
+            _ => entry_win.addstr("     "),
+        };
+        entry_win.addch('|');
         entry_win.addch('\n');
     }
 
+    // The entry the selection is on, which may be the one not yet
+    // committed.
+    fn selected_entry(&self) -> Option<&Entry> {
+        match self.input_state_.selection_ {
+            Selection::FieldSelect(ix, _) if ix < self.entries_.len() =>
+                Some(&self.entries_[ix]),
+            Selection::FieldSelect(_, _) => self.partial_entry_.as_ref(),
+            _ => None,
+        }
+    }
+
+    fn draw_values(&self) {
+        self.values_window_.clear();
+        if let Some((_, ref a)) = self.auction_ {
+            self.draw_auction(a);
+            return;
+        }
+        if let Some(ref e) = self.input_error_ {
+            set_field_cursor(&self.values_window_, FieldStatus::NotSelected);
+            self.values_window_.color_set(CURSOR_ERROR);
+            self.values_window_.mvaddstr(0, 0, e);
+            return;
+        }
+        if let Some(entry) = self.selected_entry() {
+            self.draw_score(entry);
+            self.draw_par(entry, 40);
+        }
+    }
+
+    // Calls in a grid with a column per seat, keeping the latest round in
+    // view and marking whose turn it is.
+    fn draw_auction(&self, auction : &Auction) {
+        let win = &self.values_window_;
+        set_field_cursor(win, FieldStatus::NotSelected);
+        for &seat in &SEATS {
+            win.mvaddstr(0, 3 * seat_column(seat) as i32, &seat.to_string());
+        }
+        let offset = seat_column(auction.dealer());
+        let last_row = (offset + auction.calls().len()) / 4;
+        let first_row = last_row.saturating_sub(win.get_max_y() as usize - 2);
+        let mut calls : Vec<(Seat, String)> = auction.seated_calls().into_iter()
+            .map(|(seat, call)| (seat, call_label(call))).collect();
+        if !auction.is_finished() {
+            calls.push((auction.next_seat(), String::from("?")));
+        }
+        for (i, (seat, label)) in calls.into_iter().enumerate() {
+            let row = (offset + i) / 4;
+            if row < first_row {
+                continue;
+            }
+            win.mvaddstr((row - first_row) as i32 + 1,
+                         3 * seat_column(seat) as i32, &label);
+        }
+    }
+
+    // What each part of the contract is worth, and what every result it
+    // could end with would score, with the recorded one picked out.
+    fn draw_score(&self, entry : &Entry) {
+        let win = &self.values_window_;
+        let (contract, score) = match (entry.contract(), entry.score()) {
+            (Some(c), Some(s)) => (c, s),
+            _                  => return,
+        };
+        set_field_cursor(win, FieldStatus::NotSelected);
+        win.mvaddstr(0, 0, &format!("{} {}", contract,
+                                    if entry.is_vulnerable() { "vul" }
+                                    else { "not vul" }));
+        let making = if score.trick_score() >= 100 { "Game" } else { "Part" };
+        let undertricks = format!("{}/{}/{}", score.setting,
+                                  score.next_undertricks,
+                                  score.rest_undertricks);
+        let mut lines = vec![
+            ("Tricks", score.trick_score().to_string()),
+            ("Insult", score.insult.to_string()),
+            (making, score.making_bonus.to_string()),
+            ("Slam", score.slam_bonus.to_string()),
+            ("Over", score.overtricks.to_string()),
+            ("Down", undertricks),
+        ];
+        if score.honors != 0 {
+            lines.push(("Honors", score.honors.to_string()));
+        }
+        for (y, (label, value)) in lines.iter().enumerate() {
+            win.mvaddstr(y as i32 + 1, 0,
+                         &format!("{:<6}{:>11}", label, value));
+        }
+
+        let results : Vec<i32> = possible_results(contract.number).collect();
+        let rows = results.len().div_ceil(2);
+        for (i, &r) in results.iter().enumerate() {
+            let status = if entry.result() == Some(r) {
+                FieldStatus::Selected
+            } else {
+                FieldStatus::NotSelected
+            };
+            set_field_cursor(win, status);
+            win.mvaddstr((i % rows) as i32 + 1, 19 + 10 * (i / rows) as i32,
+                         &format!("{:+3} {:+5}", r, score.score_result(r)));
+        }
+        set_field_cursor(win, FieldStatus::NotSelected);
+        win.mvaddstr(0, 19, "Results");
+    }
+
+    // The par contracts for the entry's deal, from column `x`.
+    fn draw_par(&self, entry : &Entry, x : i32) {
+        let win = &self.values_window_;
+        let table = match entry.deal().and_then(|d| self.dd_table(d)) {
+            Some(t) => t,
+            None    => return,
+        };
+        let par = entry.par(table);
+        set_field_cursor(win, FieldStatus::NotSelected);
+        win.mvaddstr(0, x, &format!("Par {:+}", par.score));
+        if par.contracts.is_empty() {
+            win.mvaddstr(1, x, "Passed out");
+        }
+        for (y, c) in par.contracts.iter().enumerate()
+            .take(win.get_max_y() as usize - 1) {
+            win.mvaddstr(y as i32 + 1, x, &c.to_string());
+        }
+    }
+
     fn draw_input(&self) {
         self.entry_window_.clear();
         self.entry_window_.mv(0, 0);
-        let mut x = 0;
-        for c in self.input().chars() {
-            self.entry_window_.addch(c);
-            x = x + 1;
+        let x = self.input().chars().count() as i32;
+        if let Some((_, ref auction)) = self.auction_ {
+            let illegal = self.input().parse::<Call>()
+                .is_ok_and(|call| auction.check(call).is_err());
+            set_field_cursor(&self.entry_window_, FieldStatus::NotSelected);
+            if illegal {
+                self.entry_window_.color_set(CURSOR_ERROR);
+            }
+            self.entry_window_.addstr(self.input());
+            self.root_window_.mv(0, x);
+            return;
         }
-        for i in x..self.entry_window_.get_max_x() {
-            self.entry_window_.addch(' ');
+        set_field_cursor(&self.entry_window_, FieldStatus::NotSelected);
+        if self.input_error_.is_some() {
+            self.entry_window_.color_set(CURSOR_ERROR);
         }
+        self.entry_window_.addstr(self.input());
         self.root_window_.mv(0, x);
     }
 
@@ -495,3 +1251,60 @@ impl Interface {
         self.root_window_.refresh();
     }
 }
+
+#[test]
+fn test_record_errors() {
+    let table = Table::new();
+    let mut entry = Entry::new(&table, Seat::North, 1);
+    assert!(record(&mut entry, &table, EntryField::Honors, "4").is_err());
+    assert!(record(&mut entry, &table, EntryField::Contract, "4s").is_ok());
+    assert!(record(&mut entry, &table, EntryField::Honors, "A")
+            == Err(String::from("Four aces need notrump")));
+    assert!(record(&mut entry, &table, EntryField::Honors, "7").is_err());
+    assert!(entry.honors().is_none());
+    assert!(record(&mut entry, &table, EntryField::Honors, "4").is_ok());
+    assert!(record(&mut entry, &table, EntryField::Contract, "4q").is_err());
+    assert!(record(&mut entry, &table, EntryField::Number, "0").is_err());
+    assert!(entry.board_num() == 1);
+    assert!(record(&mut entry, &table, EntryField::Room, "b").is_ok());
+    assert!(record(&mut entry, &table, EntryField::Room, "x")
+            == Err(String::from("Invalid room: x")));
+    assert!(entry.room().is_some());
+    assert!(record(&mut entry, &table, EntryField::Room, "").is_ok());
+    assert!(entry.room().is_none());
+}
+
+#[test]
+fn test_redo_vulnerability() {
+    let table = Table::new();
+    let mut history = History::new();
+    let before = Entry::new(&table, Seat::North, 1);
+    let mut after = before.clone();
+    assert!(record(&mut after, &table, EntryField::Vulnerability, "V")
+            .is_ok());
+    history.push(Edit::Change(0, before, after));
+    assert!(history.undo().is_some());
+    let redone = match history.redo().cloned() {
+        Some(Edit::Change(_, _, after)) => after,
+        _ => panic!("expected a change"),
+    };
+    assert!(in_mode(redone.clone(), ScoringMode::Duplicate).is_vulnerable());
+    // Board 1 is vulnerable for nobody in rubber.
+    assert!(!in_mode(redone, ScoringMode::Rubber).is_vulnerable());
+}
+
+#[test]
+fn test_layout() {
+    let wide = layout(24, 70).unwrap();
+    assert!(wide.values == (10, 56, 0, 14));
+    assert!(wide.entries == (14, 56, 10, 0));
+    let narrow = layout(24, 69).unwrap();
+    assert!(narrow.values == (10, 56, 10, 0));
+    assert!(narrow.entries == (4, 56, 20, 0));
+    assert!(layout(13, 70).is_some());
+    assert!(layout(12, 70).is_none());
+    assert!(layout(23, 69).is_some());
+    assert!(layout(22, 69).is_none());
+    assert!(layout(24, 56).is_some());
+    assert!(layout(24, 55).is_none());
+}
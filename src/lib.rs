@@ -1,30 +1,170 @@
+pub mod auction;
+pub mod board;
+pub mod butler;
+pub mod cards;
+pub mod chicago;
 pub mod contract;
 pub mod data;
+pub mod dealgen;
+pub mod dds;
+pub mod history;
+pub mod imp;
 pub mod interface;
+pub mod lin;
+pub mod movement;
+pub mod par;
 pub mod parse;
+pub mod pbn;
+pub mod play;
+pub mod rubber;
+pub mod save;
 pub mod score;
+pub mod session;
+pub mod vp;
 
 #[cfg(test)]
-use data::score_game;
+use score::{possible_results, score_game, Honors, HonorsError, HonorsKind,
+            ScoringMode};
 #[cfg(test)]
 use parse::parse_contract;
+#[cfg(test)]
+use data::{Entry, Table};
+#[cfg(test)]
+use contract::Seat;
+#[cfg(test)]
+use play::PlayError;
 
 #[test]
 fn score_3s_v_p3() {
-    assert!(score_game(parse_contract("3S").unwrap(), 3, true) == 230);
+    assert!(score_game(&parse_contract("3S").unwrap(), 3, true) == 230);
 }
 
 #[test]
 fn score_2nt_d_p4() {
-    assert!(score_game(parse_contract("2NTX").unwrap(), 4, false) == 890);
+    assert!(score_game(&parse_contract("2NTX").unwrap(), 4, false) == 890);
 }
 
 #[test]
 fn score_d_m6() {
-    assert!(score_game(parse_contract("1SX").unwrap(), -6, false) == -1400);
+    assert!(score_game(&parse_contract("1SX").unwrap(), -6, false) == -1400);
 }
 
 #[test]
 fn score_6c_v_rd_p1() {
-    assert!(score_game(parse_contract("6CXX").unwrap(), 1, true) == 2230);
+    assert!(score_game(&parse_contract("6CXX").unwrap(), 1, true) == 2230);
+}
+
+#[test]
+fn honors_checked_against_strain() {
+    let mut entry = Entry::with_mode(&Table::new(), Seat::South, 1,
+                                     ScoringMode::Chicago);
+    let aces = Honors::new(HonorsKind::FourAces, true);
+    assert!(entry.set_honors(Some(aces)) == Err(HonorsError::NoContract));
+    entry.set_contract(parse_contract("4H").unwrap());
+    assert!(entry.set_honors(Some(aces)) == Err(HonorsError::NeedsNoTrump));
+    entry.set_honors(Some(Honors::new(HonorsKind::FourTrump, false))).unwrap();
+    entry.record(0);
+    assert!(entry.value() == Some(420 - 100));
+    entry.set_contract(parse_contract("3NT").unwrap());
+    assert!(entry.honors().is_none());
+}
+
+#[test]
+fn honors_ignored_at_duplicate() {
+    let mut entry = Entry::new(&Table::new(), Seat::South, 1);
+    entry.set_contract(parse_contract("3NT").unwrap());
+    entry.set_honors(Some(Honors::new(HonorsKind::FourAces, true))).unwrap();
+    entry.record(0);
+    assert!(entry.value() == Some(400));
+}
+
+#[test]
+fn rubber_entries_skip_game_bonus() {
+    let mut entry = Entry::with_mode(&Table::new(), Seat::South, 1,
+                                     ScoringMode::Rubber);
+    entry.set_contract(parse_contract("4S").unwrap());
+    entry.record(0);
+    assert!(entry.value() == Some(120));
+    entry.set_contract(parse_contract("2HX").unwrap());
+    entry.set_honors(Some(Honors::new(HonorsKind::FourTrump, true))).unwrap();
+    entry.record(1);
+    assert!(entry.value() == Some(120 + 50 + 100 + 100));
+    entry.record(-1);
+    assert!(entry.value() == Some(-100 + 100));
+}
+
+#[test]
+fn declarer_change_follows_table() {
+    let mut table = Table::new();
+    table.set_player(Seat::East, "Erin");
+    let mut entry = Entry::new(&table, Seat::North, 2);
+    entry.set_contract(parse_contract("2H").unwrap());
+    entry.record(0);
+    let (ns, ew) = entry.vulnerability();
+    assert!(entry.is_vulnerable() == ns);
+    entry.set_declarer(&table, Seat::East);
+    assert!(entry.name() == "Erin");
+    assert!(entry.is_vulnerable() == ew);
+    assert!(entry.ns_score() == Some(-110));
+}
+
+#[test]
+fn play_decides_result() {
+    let mut entry = Entry::new(&Table::new(), Seat::North, 1);
+    assert!(entry.claim(10) == Err(PlayError::NoContract));
+    entry.set_contract(parse_contract("4S").unwrap());
+    assert!(entry.claim(10) == Err(PlayError::NoDeal));
+    entry.set_deal(Some("N:AKQJ.T98.765.432 T987.654.32.AKQJ \
+                         65432.AKQ.J4.T98 .J732.AKQT98.765".parse().unwrap()));
+    entry.play_card("CA".parse().unwrap()).unwrap();
+    entry.claim(11).unwrap();
+    assert!(entry.value() == Some(450));
+    // A different result typed in by hand wins over the play.
+    entry.record(0);
+    assert!(entry.play().is_none() && entry.value() == Some(420));
+    entry.claim(10).unwrap();
+    entry.set_contract(parse_contract("4H").unwrap());
+    assert!(entry.play().is_none());
+}
+
+#[test]
+fn duplicate_boards_follow_cycle() {
+    let table = Table::new();
+    assert!(!Entry::new(&table, Seat::East, 5).is_vulnerable());
+    assert!(Entry::new(&table, Seat::South, 5).is_vulnerable());
+    let mut entry = Entry::new(&table, Seat::East, 9);
+    entry.set_contract(parse_contract("3NT").unwrap());
+    entry.record(0);
+    assert!(entry.value() == Some(600));
+}
+
+#[test]
+fn score_breakdown() {
+    let mut entry = Entry::new(&Table::new(), Seat::North, 2);
+    assert!(entry.score().is_none());
+    entry.set_contract(parse_contract("4SX").unwrap());
+    let score = entry.score().unwrap();
+    assert!(score.trick_score() == 240);
+    assert!(score.insult == 50);
+    assert!(score.making_bonus == 500);
+    assert!(score.slam_bonus == 0);
+    assert!(score.overtricks == 200);
+    let results = possible_results(entry.contract().unwrap().number);
+    assert!(results == (-10..=3));
+    assert!(score.score_result(*results.start()) == -2900);
+    assert!(score.score_result(*results.end()) == 1390);
+}
+
+#[test]
+fn board_change_follows_cycle() {
+    let mut entry = Entry::new(&Table::new(), Seat::East, 1);
+    entry.set_contract(parse_contract("4H").unwrap());
+    entry.record(0);
+    assert!(entry.value() == Some(420));
+    assert!(!entry.set_board_num(0));
+    assert!(entry.board_num() == 1);
+    // Board 3 has East-West vulnerable.
+    assert!(entry.set_board_num(3));
+    assert!(entry.value() == Some(620));
+    assert!(entry.vulnerability() == (false, true));
 }
@@ -0,0 +1,352 @@
+use std::fmt;
+
+use auction::{Auction, Call};
+use cards::{Card, Deal, Rank, Suit};
+use contract::{Contract, Seat};
+use data::{Entry, Table};
+use imp::Room;
+use play::trick_winner;
+
+// Bridge Base Online's LIN format: a flat list of `key|value|` pairs, where
+// `qx` (or `md` in hand records without it) starts each board.
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum LinError {
+    // Token key and what was wrong with its value.
+    BadToken(String, String),
+}
+
+impl fmt::Display for LinError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LinError::BadToken(ref key, ref e) => write!(f, "{}|: {}", key, e),
+        }
+    }
+}
+
+pub struct LinBoard {
+    pub board_num : u32,
+    pub room : Option<Room>,
+    pub vulnerability : (bool, bool),
+    // None if the file has no hands.
+    pub deal : Option<Deal>,
+    pub auction : Auction,
+    // None when the board was passed out or the auction is unfinished.
+    pub contract : Option<Contract>,
+    pub declarer : Option<Seat>,
+    pub tricks : Option<i32>,
+    pub play : Vec<Card>,
+    // Declarer's tricks in all, when play ended in a claim.
+    pub claim : Option<i32>,
+}
+
+impl LinBoard {
+    fn new(board_num : u32) -> LinBoard {
+        LinBoard {
+            board_num,
+            room : None,
+            vulnerability : (false, false),
+            deal : None,
+            auction : Auction::new(Seat::North),
+            contract : None,
+            declarer : None,
+            tricks : None,
+            play : Vec::new(),
+            claim : None,
+        }
+    }
+
+    pub fn to_entry(&self, table : &Table) -> Option<Entry> {
+        let (contract, declarer) = (self.contract?, self.declarer?);
+        let mut entry = Entry::new(table, declarer, self.board_num);
+        entry.set_vulnerability(self.vulnerability.0, self.vulnerability.1);
+        entry.set_contract(contract);
+        if let Some(t) = self.tricks {
+            entry.record(t - 6 - contract.number.into_i32());
+        }
+        entry.set_room(self.room);
+        entry.set_deal(self.deal);
+        // The play log is only kept if it checks out against the deal.
+        let mut logged = self.play.iter().try_for_each(|&c| entry.play_card(c));
+        // Some logs claim after the last card too, with nothing left to
+        // claim.
+        if let (Ok(()), Some(t)) = (logged, self.claim) {
+            if entry.play().is_none_or(|p| !p.is_finished()) {
+                logged = entry.claim(t as u32);
+            }
+        }
+        if logged.is_err() {
+            entry.clear_play();
+        }
+        Some(entry)
+    }
+}
+
+// LIN numbers seats from South, going clockwise.
+fn lin_seat(c : char) -> Option<Seat> {
+    match c {
+        '1' => Some(Seat::South),
+        '2' => Some(Seat::West),
+        '3' => Some(Seat::North),
+        '4' => Some(Seat::East),
+        _   => None,
+    }
+}
+
+// Hands after the dealer digit, South, West, North then East, like
+// "SAKQH432D...C...". East is often left out, and is then whatever the
+// other three don't hold.
+fn lin_deal(hands : &str) -> Result<Option<Deal>, String> {
+    let order = [Seat::South, Seat::West, Seat::North, Seat::East];
+    let mut deal = Deal::new();
+    for (&seat, text) in order.iter().zip(hands.split(',')) {
+        let mut suit = None;
+        for c in text.chars() {
+            if let Some(s) = Suit::from_char(c) {
+                suit = Some(s);
+                continue;
+            }
+            match (suit, Rank::from_char(c)) {
+                (Some(s), Some(r)) => {
+                    if deal.holder(Card::new(s, r)).is_some() {
+                        return Err(format!("Repeated card: {}{}", s, r));
+                    }
+                    deal.hand_mut(seat).insert(Card::new(s, r));
+                },
+                _ => return Err(format!("Invalid hand: {}", text)),
+            }
+        }
+    }
+    let known = order.iter().filter(|&&s| !deal.hand(s).is_empty()).count();
+    if known == 0 {
+        return Ok(None);
+    }
+    if known == 3 && deal.hand(Seat::East).is_empty() {
+        for ix in 0..52 {
+            let card = Card::from_index(ix);
+            if deal.holder(card).is_none() {
+                deal.hand_mut(Seat::East).insert(card);
+            }
+        }
+    }
+    Ok(Some(deal))
+}
+
+fn lin_vulnerability(value : &str) -> Option<(bool, bool)> {
+    match value.to_lowercase().as_str() {
+        "o" | "0" | "-" | "" => Some((false, false)),
+        "n"                  => Some((true, false)),
+        "e"                  => Some((false, true)),
+        "b"                  => Some((true, true)),
+        _                    => None,
+    }
+}
+
+// Declarer's tricks, if all thirteen were played out.
+// The deal may be missing, so nothing is checked.
+fn count_tricks(contract : Contract, declarer : Seat, play : &[Card])
+    -> Option<i32> {
+    if play.len() < 52 {
+        return None;
+    }
+    let trump = Suit::from_strain(contract.suit);
+    let mut leader = declarer.next();
+    let mut tricks = 0;
+    for trick in play.chunks(4) {
+        leader = trick_winner(leader, trick, trump);
+        if leader.side() == declarer.side() {
+            tricks += 1;
+        }
+    }
+    Some(tricks)
+}
+
+fn tokens(text : &str) -> Vec<(String, String)> {
+    let parts : Vec<&str> = text.split('|').map(str::trim).collect();
+    parts.chunks(2)
+        .filter(|p| p.len() == 2)
+        .map(|p| (p[0].to_lowercase(), String::from(p[1])))
+        .collect()
+}
+
+fn finish(mut board : LinBoard) -> LinBoard {
+    if let Some((contract, declarer)) = board.auction.result() {
+        board.contract = Some(contract);
+        board.declarer = Some(declarer);
+        board.tricks = board.claim.or_else(
+            || count_tricks(contract, declarer, &board.play));
+    }
+    board
+}
+
+// Players are read from the `pn` token, South first.
+pub fn parse_lin(text : &str) -> Result<(Table, Vec<LinBoard>), LinError> {
+    let mut table = Table::new();
+    let mut boards = Vec::new();
+    let mut current : Option<LinBoard> = None;
+    let bad = |key : &str, e : &str| {
+        LinError::BadToken(String::from(key), String::from(e))
+    };
+    for (key, value) in tokens(text) {
+        match key.as_str() {
+            "pn" => {
+                let order = [Seat::South, Seat::West, Seat::North, Seat::East];
+                for (&seat, name) in order.iter().zip(value.split(',')) {
+                    if !name.is_empty() {
+                        table.set_player(seat, name);
+                    }
+                }
+            },
+            "qx" => {
+                boards.extend(current.take().map(finish));
+                let room = match value.chars().next() {
+                    Some('o') => Some(Room::Open),
+                    Some('c') => Some(Room::Closed),
+                    _         => None,
+                };
+                let number =
+                    value.trim_start_matches(|c : char| c.is_alphabetic());
+                let mut board = LinBoard::new(
+                    number.parse().map_err(|_| bad(&key, "Invalid board"))?);
+                board.room = room;
+                current = Some(board);
+            },
+            "md" => {
+                // Hand records without qx start a new board with every deal.
+                if current.as_ref()
+                    .is_none_or(|b| !b.auction.calls().is_empty()) {
+                    let next = boards.len() as u32 + 1;
+                    boards.extend(current.take().map(finish));
+                    current = Some(LinBoard::new(next));
+                }
+                let dealer = value.chars().next().and_then(lin_seat)
+                    .ok_or_else(|| bad(&key, "Invalid dealer"))?;
+                let deal = lin_deal(&value[1..]).map_err(|e| bad(&key, &e))?;
+                let board = current.as_mut().unwrap();
+                board.auction = Auction::new(dealer);
+                board.deal = deal;
+            },
+            "ah" => if let Some(ref mut b) = current {
+                if let Some(n) = value.split_whitespace().last()
+                    .and_then(|n| n.parse().ok()) {
+                    b.board_num = n;
+                }
+            },
+            "sv" => if let Some(ref mut b) = current {
+                b.vulnerability = lin_vulnerability(&value)
+                    .ok_or_else(|| bad(&key, "Invalid vulnerability"))?;
+            },
+            "mb" => if let Some(ref mut b) = current {
+                let call : Call = value.trim_end_matches('!').parse()
+                    .map_err(|e : String| bad(&key, &e))?;
+                b.auction.call(call)
+                    .map_err(|e| bad(&key, &format!("{:?}", e)))?;
+            },
+            "pc" => if let Some(ref mut b) = current {
+                b.play.push(value.parse().map_err(|e : String| bad(&key, &e))?);
+            },
+            "mc" => if let Some(ref mut b) = current {
+                b.claim = Some(value.parse()
+                               .map_err(|_| bad(&key, "Invalid claim"))?);
+            },
+            _ => (),
+        }
+    }
+    boards.extend(current.take().map(finish));
+    Ok((table, boards))
+}
+
+// Boards that were passed out, or whose auction never finished, are
+// dropped.
+pub fn read_lin(text : &str) -> Result<(Table, Vec<Entry>), LinError> {
+    let (table, boards) = parse_lin(text)?;
+    let entries = boards.iter().filter_map(|b| b.to_entry(&table)).collect();
+    Ok((table, entries))
+}
+
+#[test]
+fn test_lin_deal() {
+    let text = "md|2S65432HAKQDJ4CT98,S\
+                HJ732DAKQT98C765,SAKQJHT98D765C432,|";
+    let (_, boards) = parse_lin(text).unwrap();
+    let deal = boards[0].deal.unwrap();
+    assert!(deal.validate().is_ok());
+    assert!(deal.to_pbn(Seat::North) ==
+            "N:AKQJ.T98.765.432 T987.654.32.AKQJ 65432.AKQ.J4.T98 \
+             .J732.AKQT98.765");
+    assert!(boards[0].auction.dealer() == Seat::West);
+}
+
+#[test]
+fn test_read_lin() {
+    let text = "pn|Sue,Walt,Nina,Ed|st||\
+                qx|o1|md|3SAKQ,,,|sv|o|ah|Board 1|\
+                mb|1S|mb|p|mb|4S|mb|p|mb|p|mb|p|\
+                pc|HK|pc|HA|pc|H2|pc|H3|mc|11|pg||\
+                qx|c2|md|4|sv|n|mb|1N!|mb|d|mb|p|mb|p|mb|p|pg||\
+                qx|o3|md|1|sv|b|mb|p|mb|p|mb|p|mb|p|pg||";
+    let (table, boards) = parse_lin(text).unwrap();
+    assert!(table.get_player(Seat::West) == "Walt");
+    assert!(table.get_player(Seat::North) == "Nina");
+    assert!(boards.len() == 3);
+    assert!(boards[0].auction.dealer() == Seat::North);
+    assert!(boards[0].declarer == Some(Seat::North));
+    assert!(boards[0].tricks == Some(11));
+    assert!(boards[0].play.len() == 4);
+    assert!(boards[0].deal.unwrap().hand(Seat::South).len() == 3);
+    assert!(boards[1].deal.is_none());
+    assert!(boards[1].room == Some(Room::Closed));
+    assert!(boards[1].contract.unwrap().to_string() == "1NTX");
+    assert!(boards[1].declarer == Some(Seat::East));
+    assert!(boards[1].tricks.is_none());
+    assert!(boards[2].contract.is_none());
+
+    let (_, entries) = read_lin(text).unwrap();
+    assert!(entries.len() == 2);
+    assert!(entries[0].name() == "Nina");
+    assert!(entries[0].value() == Some(450));
+    assert!(entries[1].vulnerability() == (true, false));
+    assert!(entries[1].result().is_none());
+
+    assert!(parse_lin("qx|o1|md|9|").is_err());
+    assert!(parse_lin("qx|o1|md|3SAA,,,|").is_err());
+    assert!(parse_lin("qx|o1|md|3|mb|1S|mb|1H|").is_err());
+}
+
+#[test]
+fn test_lin_play() {
+    let board = "md|2S65432HAKQDJ4CT98,S\
+                 HJ732DAKQT98C765,SAKQJHT98D765C432,|sv|o|\
+                 mb|p|mb|1S|mb|p|mb|4S|mb|p|mb|p|mb|p|";
+    let text = format!("{}pc|CA|pc|C8|pc|C5|pc|C2|\
+                        pc|CK|pc|C9|pc|C6|pc|C3|mc|10|", board);
+    let (_, entries) = read_lin(&text).unwrap();
+    let play = entries[0].play().unwrap();
+    assert!(play.cards().len() == 8);
+    assert!(play.winners() == vec![Seat::East, Seat::East]);
+    assert!(play.claimed() == Some(10));
+    assert!(entries[0].value() == Some(420));
+    // South revokes, so only the claim is kept.
+    let text = format!("{}pc|CA|pc|HA|mc|10|", board);
+    let (_, entries) = read_lin(&text).unwrap();
+    assert!(entries[0].play().is_none());
+    assert!(entries[0].result() == Some(0));
+    // A claim after all 52 cards changes nothing.
+    let text = format!("{}pc|D2|pc|D4|pc|D8|pc|D5|\
+                        pc|H2|pc|H8|pc|H4|pc|HQ|\
+                        pc|S2|pc|H3|pc|SJ|pc|S7|\
+                        pc|C2|pc|CJ|pc|C8|pc|C5|\
+                        pc|D3|pc|DJ|pc|D9|pc|D6|\
+                        pc|S3|pc|C6|pc|SQ|pc|S8|\
+                        pc|C3|pc|CQ|pc|C9|pc|C7|\
+                        pc|H5|pc|HK|pc|H7|pc|H9|\
+                        pc|S4|pc|DT|pc|SK|pc|S9|\
+                        pc|C4|pc|CK|pc|CT|pc|HJ|\
+                        pc|H6|pc|HA|pc|DQ|pc|HT|\
+                        pc|S5|pc|DK|pc|SA|pc|ST|\
+                        pc|D7|pc|CA|pc|S6|pc|DA|\
+                        mc|9|", board);
+    let (_, entries) = read_lin(&text).unwrap();
+    assert!(entries[0].play().is_some_and(|p| p.cards().len() == 52));
+    assert!(entries[0].result() == Some(-1));
+}
@@ -2,8 +2,16 @@ extern crate pancurses;
 extern crate brodg;
 
 use pancurses::{initscr, init_pair, endwin};
+use brodg::dealgen::DealGenerator;
 use brodg::interface::Interface;
+use brodg::pbn::write_deals;
+use brodg::save::{load_session, SavedSession};
+use std::env;
+use std::io;
 use std::panic;
+use std::path::PathBuf;
+use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 const CURSOR_NORMAL     : i16 = 1;
 const CURSOR_ERROR      : i16 = 3;
@@ -12,7 +20,7 @@ const CURSOR_REDOUBLED  : i16 = 4;
 const CURSOR_CONTRACT   : i16 = 5;
 const CURSOR_ENTERING   : i16 = 6;
 
-fn real_main() {
+fn real_main(saved : Option<SavedSession>, save_path : PathBuf) {
     let window = initscr();
     window.keypad(true);
     pancurses::start_color();
@@ -25,7 +33,18 @@ fn real_main() {
     init_pair(CURSOR_CONTRACT,  pancurses::COLOR_GREEN, pancurses::COLOR_BLACK);
     init_pair(CURSOR_ENTERING,  pancurses::COLOR_WHITE, pancurses::COLOR_BLUE);
     window.refresh();
-    let mut interface = Interface::new(window);
+    let mut interface = match Interface::new(window) {
+        Ok(i) => i,
+        Err(e) => {
+            endwin();
+            eprintln!("{}", e);
+            process::exit(1);
+        },
+    };
+    if let Some(saved) = saved {
+        interface.restore(saved);
+    }
+    interface.set_save_path(save_path);
     loop {
         interface.redraw();
         if !interface.get_input() {
@@ -34,8 +53,56 @@ fn real_main() {
     }
 }
 
+fn usage() -> ! {
+    eprintln!("usage: brodg [--load <file>]");
+    eprintln!("       brodg --deal <seed> <boards>");
+    process::exit(2);
+}
+
+// New sessions get their own file so that nothing older is overwritten.
+fn new_save_path() -> PathBuf {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs()).unwrap_or(0);
+    PathBuf::from(format!("brodg-{}.session", now))
+}
+
+// Prints hand records for a club night; the same seed gives the same boards.
+fn deal_boards(seed : &str, boards : &str) {
+    let (seed, boards) : (u64, usize) = match (seed.parse(), boards.parse()) {
+        (Ok(s), Ok(b)) => (s, b),
+        _              => usage(),
+    };
+    let mut generator = DealGenerator::new(seed);
+    let deals : Vec<_> =
+        (0..boards).filter_map(|_| generator.next_deal()).collect();
+    let event = format!("Seed {}", seed);
+    if let Err(e) = write_deals(&mut io::stdout(), &event, &deals) {
+        eprintln!("Could not write deals: {}", e);
+        process::exit(1);
+    }
+}
+
 fn main() {
-    let result = panic::catch_unwind(|| { real_main(); });
+    let args : Vec<String> = env::args().skip(1).collect();
+    if args.len() == 3 && args[0] == "--deal" {
+        deal_boards(&args[1], &args[2]);
+        return;
+    }
+    let (saved, save_path) = match args.len() {
+        0 => (None, new_save_path()),
+        2 if args[0] == "--load" => {
+            let path = PathBuf::from(&args[1]);
+            match load_session(&path) {
+                Ok(saved) => (Some(saved), path),
+                Err(e) => {
+                    eprintln!("Could not load {}: {}", path.display(), e);
+                    process::exit(1);
+                },
+            }
+        },
+        _ => usage(),
+    };
+    let result = panic::catch_unwind(move || { real_main(saved, save_path); });
     endwin();
     println!("{:?}", result);
 }
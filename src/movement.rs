@@ -0,0 +1,584 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+use contract::Side;
+use session::PairId;
+
+// Movements say which pairs sit where and which boards they play in each
+// round. Boards go out in sets of `boards_per_round`, with set 1 holding
+// boards 1 to `boards_per_round` and so on.
+
+// One table in one round.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Assignment {
+    pub table : u32,
+    pub ns_pair : PairId,
+    pub ew_pair : PairId,
+    pub boards : Vec<u32>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Movement {
+    tables_ : u32,
+    boards_per_round_ : u32,
+    // Each round's assignments, in table order.
+    rounds_ : Vec<Vec<Assignment>>,
+}
+
+impl Movement {
+    pub fn tables(&self) -> u32 {
+        self.tables_
+    }
+
+    pub fn boards_per_round(&self) -> u32 {
+        self.boards_per_round_
+    }
+
+    pub fn rounds(&self) -> &[Vec<Assignment>] {
+        &self.rounds_
+    }
+
+    // The pairs that play `board` at `table`, in whichever round that is.
+    pub fn find(&self, table : u32, board : u32) -> Option<&Assignment> {
+        self.rounds_.iter().flat_map(|r| r.iter())
+            .find(|a| a.table == table && a.boards.contains(&board))
+    }
+
+    // Every pair that plays at least once.
+    pub fn pairs(&self) -> Vec<PairId> {
+        let mut pairs : Vec<PairId> = self.rounds_.iter()
+            .flat_map(|r| r.iter())
+            .flat_map(|a| vec![a.ns_pair, a.ew_pair])
+            .collect();
+        pairs.sort();
+        pairs.dedup();
+        pairs
+    }
+
+    // None for a pair that never plays.
+    pub fn guide_card(&self, pair : PairId) -> Option<GuideCard> {
+        if !self.pairs().contains(&pair) {
+            return None;
+        }
+        let stops = self.rounds_.iter().map(|round| {
+            round.iter().filter_map(|a| {
+                let (side, opponents) = if a.ns_pair == pair {
+                    (Side::NorthSouth, a.ew_pair)
+                } else if a.ew_pair == pair {
+                    (Side::EastWest, a.ns_pair)
+                } else {
+                    return None;
+                };
+                Some(Stop {
+                    table : a.table,
+                    side,
+                    opponents,
+                    boards : a.boards.clone(),
+                })
+            }).next()
+        }).collect();
+        Some(GuideCard {
+            pair,
+            stops,
+        })
+    }
+
+    // Checks that no pair is in two places at once or plays a board twice,
+    // and that every board is played as often as the most played one.
+    pub fn validate(&self) -> Result<(), MovementError> {
+        let mut played : BTreeMap<PairId, Vec<u32>> = BTreeMap::new();
+        let mut plays : BTreeMap<u32, u32> = BTreeMap::new();
+        for (r, round) in self.rounds_.iter().enumerate() {
+            let round_num = r as u32 + 1;
+            let mut tables = Vec::new();
+            let mut pairs = Vec::new();
+            for a in round {
+                if tables.contains(&a.table) {
+                    return Err(MovementError::TableTwice(round_num, a.table));
+                }
+                tables.push(a.table);
+                for &pair in &[a.ns_pair, a.ew_pair] {
+                    if pairs.contains(&pair) {
+                        return Err(MovementError::PairTwice(round_num, pair));
+                    }
+                    pairs.push(pair);
+                    let boards = played.entry(pair).or_default();
+                    if let Some(&b) = a.boards.iter()
+                        .find(|b| boards.contains(b)) {
+                        return Err(MovementError::BoardTwice(pair, b));
+                    }
+                    boards.extend(a.boards.iter().cloned());
+                }
+                for &board in &a.boards {
+                    *plays.entry(board).or_insert(0) += 1;
+                }
+            }
+        }
+        let expected = plays.values().cloned().max().unwrap_or(0);
+        match plays.iter().find(|&(_, &n)| n != expected) {
+            Some((&board, &n)) =>
+                Err(MovementError::Unbalanced(board, n, expected)),
+            None => Ok(()),
+        }
+    }
+}
+
+// Where a pair goes in one round.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Stop {
+    pub table : u32,
+    pub side : Side,
+    pub opponents : PairId,
+    pub boards : Vec<u32>,
+}
+
+// What a pair needs to know to get round the room: one stop per round, or
+// None for a round they sit out.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct GuideCard {
+    pub pair : PairId,
+    pub stops : Vec<Option<Stop>>,
+}
+
+// Runs of boards as "1-3", the rest one by one.
+fn board_list(boards : &[u32]) -> String {
+    let mut runs : Vec<(u32, u32)> = Vec::new();
+    for &b in boards {
+        match runs.last_mut() {
+            Some(run) if run.1 + 1 == b => run.1 = b,
+            _ => runs.push((b, b)),
+        }
+    }
+    runs.iter().map(|&(first, last)| {
+        if first == last {
+            first.to_string()
+        } else {
+            format!("{}-{}", first, last)
+        }
+    }).collect::<Vec<String>>().join(", ")
+}
+
+// Like:
+//   Pair 3
+//   Round 1: table 2 NS v 5, boards 3-4
+//   Round 2: sit out
+impl fmt::Display for GuideCard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Pair {}", self.pair)?;
+        for (r, stop) in self.stops.iter().enumerate() {
+            write!(f, "Round {}: ", r + 1)?;
+            match *stop {
+                Some(ref s) => {
+                    let side = match s.side {
+                        Side::NorthSouth => "NS",
+                        Side::EastWest   => "EW",
+                    };
+                    writeln!(f, "table {} {} v {}, boards {}", s.table, side,
+                             s.opponents, board_list(&s.boards))?;
+                },
+                None => writeln!(f, "sit out")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum MovementError {
+    Io(io::Error),
+    // Line number and what was wrong with it.
+    BadLine(usize, String),
+    // Round and table or pair.
+    TableTwice(u32, u32),
+    PairTwice(u32, PairId),
+    BoardTwice(PairId, u32),
+    // Board, how often it's played, and how often it should be.
+    Unbalanced(u32, u32, u32),
+}
+
+impl fmt::Display for MovementError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            MovementError::Io(ref e)         => write!(f, "{}", e),
+            MovementError::BadLine(n, ref e) => write!(f, "Line {}: {}", n, e),
+            MovementError::TableTwice(r, t)  =>
+                write!(f, "Round {}: table {} is used twice", r, t),
+            MovementError::PairTwice(r, p)   =>
+                write!(f, "Round {}: pair {} is in two places", r, p),
+            MovementError::BoardTwice(p, b)  =>
+                write!(f, "Pair {} plays board {} twice", p, b),
+            MovementError::Unbalanced(b, n, expected) =>
+                write!(f, "Board {} is played {} times, not {}", b, n,
+                       expected),
+        }
+    }
+}
+
+impl From<io::Error> for MovementError {
+    fn from(e : io::Error) -> MovementError {
+        MovementError::Io(e)
+    }
+}
+
+fn board_set(set : u32, boards_per_round : u32) -> Vec<u32> {
+    (set * boards_per_round + 1..=(set + 1) * boards_per_round).collect()
+}
+
+// An even number of tables would bring every EW pair back to the boards
+// they started with halfway through, so the movement has to be broken.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum EvenTables {
+    // EW pairs skip a table halfway through, which leaves one round fewer
+    // than there are tables.
+    Skip,
+    // A bye stand halfway round holds one set out of play each round, and
+    // the first and last tables share a set.
+    RelayAndBye,
+}
+
+// NS pair t sits at table t throughout, and EW pair `tables` + t starts
+// there. EW pairs move up a table each round and boards move down one.
+// With an odd number of tables every EW pair meets every NS pair and plays
+// every board, and `even` is ignored.
+//
+// None for no tables or no boards, or a relay with fewer than four tables.
+pub fn mitchell(tables : u32, boards_per_round : u32, even : EvenTables)
+    -> Option<Movement> {
+    if tables == 0 || boards_per_round == 0 {
+        return None;
+    }
+    let n = tables;
+    let relay = n.is_multiple_of(2) && even == EvenTables::RelayAndBye;
+    if relay && n < 4 {
+        return None;
+    }
+    let skip = n.is_multiple_of(2) && !relay;
+    // The places boards pass through, with the bye stand as None. Set k
+    // starts in place k and boards move down a place each round, from the
+    // first place round to the last. The last table of a relay shares the
+    // first table's boards.
+    let circuit : Vec<Option<u32>> = if relay {
+        let half = n / 2;
+        (1..=half).map(Some)
+            .chain(Some(None))
+            .chain((half + 1..n).map(Some))
+            .collect()
+    } else {
+        (1..=n).map(Some).collect()
+    };
+    let rounds = if skip { n - 1 } else { n };
+    let rounds_ = (0..rounds).map(|r| {
+        // How far the EW pairs have moved, counting the skipped table.
+        let moved = if skip && r >= n / 2 { r + 1 } else { r };
+        (1..=n).map(|t| {
+            let place = if relay && t == n { 1 } else { t };
+            let position = circuit.iter()
+                .position(|&p| p == Some(place)).unwrap() as u32;
+            let set = (position + r) % n;
+            Assignment {
+                table : t,
+                ns_pair : t,
+                ew_pair : n + (t - 1 + n - moved % n) % n + 1,
+                boards : board_set(set, boards_per_round),
+            }
+        }).collect()
+    }).collect();
+    Some(Movement {
+        tables_ : n,
+        boards_per_round_ : boards_per_round,
+        rounds_,
+    })
+}
+
+// For n tables, how far ahead of the round number each table's set is,
+// from table 1 to table n - 1. Any table's pairs between them play every
+// set once. Three and five tables can't give every table its own set, so
+// some share boards with each other.
+const HOWELL_SETS : [&[u32]; 6] = [
+    &[0, 0],
+    &[2, 4, 1],
+    &[2, 6, 8, 2],
+    &[3, 5, 9, 1, 4],
+    &[3, 5, 9, 1, 6, 2],
+    &[2, 7, 11, 6, 1, 13, 5],
+];
+
+// Every pair meets every other pair and plays every board, over one round
+// fewer than there are pairs. The last pair stays at the last table and
+// the rest move round the others. With an odd number of pairs the last
+// table is left out, and whoever would have sat there sits out.
+//
+// None for fewer than five pairs or more than sixteen, or no boards.
+pub fn howell(pairs : u32, boards_per_round : u32) -> Option<Movement> {
+    if !(5..=16).contains(&pairs) || boards_per_round == 0 {
+        return None;
+    }
+    let n = pairs.div_ceil(2);
+    let rounds = 2 * n - 1;
+    let offsets = HOWELL_SETS[n as usize - 3];
+    let rounds_ = (0..rounds).map(|r| {
+        let mut round : Vec<Assignment> = offsets.iter().enumerate()
+            .map(|(k, &offset)| {
+                let k = k as u32 + 1;
+                let up = (r + k) % rounds + 1;
+                let down = (r + rounds - k) % rounds + 1;
+                let (ns_pair, ew_pair) = if (r + k).is_multiple_of(2) {
+                    (up, down)
+                } else {
+                    (down, up)
+                };
+                Assignment {
+                    table : k,
+                    ns_pair,
+                    ew_pair,
+                    boards : board_set((r + offset) % rounds,
+                                       boards_per_round),
+                }
+            }).collect();
+        if pairs == 2 * n {
+            let (ns_pair, ew_pair) = if r.is_multiple_of(2) {
+                (2 * n, r + 1)
+            } else {
+                (r + 1, 2 * n)
+            };
+            round.push(Assignment {
+                table : n,
+                ns_pair,
+                ew_pair,
+                boards : board_set(r, boards_per_round),
+            });
+        }
+        round
+    }).collect();
+    Some(Movement {
+        tables_ : pairs / 2,
+        boards_per_round_ : boards_per_round,
+        rounds_,
+    })
+}
+
+// A line per round after the number of boards per round, like:
+//
+//   # Three table Mitchell
+//   boards 2
+//   round 1-4/1 2-5/2 3-6/3
+//   round 1-6/2 2-4/3 3-5/1
+//
+// Each table in turn is "NS-EW/set", with sets counted from 1, or "-" when
+// the table isn't in play that round.
+fn parse_round(text : &str, boards_per_round : u32)
+    -> Result<Vec<Assignment>, String> {
+    let mut round = Vec::new();
+    for (t, table) in text.split_whitespace().enumerate() {
+        if table == "-" {
+            continue;
+        }
+        let bad = || format!("Invalid table: {}", table);
+        let (pairs, set) = table.split_once('/').ok_or_else(bad)?;
+        let (ns, ew) = pairs.split_once('-').ok_or_else(bad)?;
+        let set : u32 = set.parse().map_err(|_| bad())?;
+        if set == 0 {
+            return Err(bad());
+        }
+        round.push(Assignment {
+            table : t as u32 + 1,
+            ns_pair : ns.parse().map_err(|_| bad())?,
+            ew_pair : ew.parse().map_err(|_| bad())?,
+            boards : board_set(set - 1, boards_per_round),
+        });
+    }
+    Ok(round)
+}
+
+// Reads a custom movement and checks it with `validate`.
+pub fn read_movement<R : BufRead>(input : R)
+    -> Result<Movement, MovementError> {
+    let mut boards_per_round = None;
+    let mut rounds_ = Vec::new();
+    let mut tables_ = 0;
+    for (n, line) in input.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let bad = |e : &str| MovementError::BadLine(n + 1, String::from(e));
+        let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+        match key {
+            "boards" => {
+                let boards = value.trim().parse().map_err(|_| {
+                    bad("Invalid number of boards")
+                })?;
+                if boards == 0 || boards_per_round.is_some() {
+                    return Err(bad("Invalid number of boards"));
+                }
+                boards_per_round = Some(boards);
+            },
+            "round"  => {
+                let boards = boards_per_round.ok_or_else(|| {
+                    bad("Boards per round must come first")
+                })?;
+                let round = parse_round(value, boards)
+                    .map_err(|e| MovementError::BadLine(n + 1, e))?;
+                tables_ = round.iter().map(|a| a.table).fold(tables_, u32::max);
+                rounds_.push(round);
+            },
+            _        => return Err(bad(&format!("Unknown key: {}", key))),
+        }
+    }
+    if rounds_.is_empty() {
+        return Err(MovementError::BadLine(0, String::from("No rounds")));
+    }
+    let movement = Movement {
+        tables_,
+        boards_per_round_ : boards_per_round.unwrap(),
+        rounds_,
+    };
+    movement.validate()?;
+    Ok(movement)
+}
+
+pub fn load_movement(path : &Path) -> Result<Movement, MovementError> {
+    read_movement(BufReader::new(File::open(path)?))
+}
+
+// Every pair plays each board at most once and meets each other pair at
+// most once. Returns how many times each pair played.
+#[cfg(test)]
+fn check_fair(movement : &Movement) -> Vec<(PairId, usize)> {
+    let mut seen : BTreeMap<PairId, (Vec<u32>, Vec<PairId>)> =
+        BTreeMap::new();
+    for round in movement.rounds() {
+        for a in round {
+            for &(pair, other) in &[(a.ns_pair, a.ew_pair),
+                                    (a.ew_pair, a.ns_pair)] {
+                let played = seen.entry(pair).or_default();
+                assert!(!played.1.contains(&other));
+                assert!(a.boards.iter().all(|b| !played.0.contains(b)));
+                played.0.extend(a.boards.iter().cloned());
+                played.1.push(other);
+            }
+        }
+    }
+    seen.into_iter().map(|(pair, p)| (pair, p.1.len())).collect()
+}
+
+#[test]
+fn test_odd_mitchell() {
+    let m = mitchell(5, 3, EvenTables::Skip).unwrap();
+    assert!(m.rounds().len() == 5);
+    assert!(check_fair(&m).iter().all(|&(_, n)| n == 5));
+    // EW 6 starts at table 1 and moves up; boards 4-6 move down.
+    assert!(m.rounds()[1][1].ew_pair == 6);
+    assert!(m.rounds()[0][1].boards == vec![4, 5, 6]);
+    assert!(m.rounds()[1][0].boards == vec![4, 5, 6]);
+    assert!(m.rounds()[1][4].boards == vec![1, 2, 3]);
+    assert!(m.find(3, 13).unwrap().ew_pair == 6);
+    assert!(m.find(3, 16).is_none());
+    assert!(mitchell(0, 3, EvenTables::Skip).is_none());
+}
+
+#[test]
+fn test_even_mitchell() {
+    let m = mitchell(6, 2, EvenTables::Skip).unwrap();
+    assert!(m.rounds().len() == 5);
+    assert!(check_fair(&m).iter().all(|&(_, n)| n == 5));
+
+    let m = mitchell(6, 2, EvenTables::RelayAndBye).unwrap();
+    assert!(m.rounds().len() == 6);
+    assert!(check_fair(&m).iter().all(|&(_, n)| n == 6));
+    for round in m.rounds() {
+        assert!(round[0].boards == round[5].boards);
+        let mut boards : Vec<u32> =
+            round.iter().flat_map(|a| a.boards.iter().cloned()).collect();
+        boards.sort();
+        boards.dedup();
+        // One set is on the bye stand.
+        assert!(boards.len() == 10);
+    }
+    assert!(mitchell(2, 2, EvenTables::RelayAndBye).is_none());
+}
+
+#[test]
+fn test_howell() {
+    for tables in 3..=8 {
+        let m = howell(2 * tables, 2).unwrap();
+        let rounds = 2 * tables as usize - 1;
+        assert!(m.tables() == tables);
+        assert!(m.rounds().len() == rounds);
+        // Meeting every other pair once means playing every round.
+        assert!(check_fair(&m).iter().all(|&(_, n)| n == rounds));
+        assert!(m.validate().is_ok());
+    }
+    assert!(howell(4, 2).is_none());
+    assert!(howell(18, 2).is_none());
+}
+
+#[test]
+fn test_howell_sit_out() {
+    let m = howell(7, 3).unwrap();
+    assert!(m.tables() == 3);
+    assert!(m.pairs() == (1..=7).collect::<Vec<PairId>>());
+    assert!(m.validate().is_ok());
+    for pair in 1..=7 {
+        let card = m.guide_card(pair).unwrap();
+        assert!(card.stops.len() == 7);
+        assert!(card.stops.iter().filter(|s| s.is_none()).count() == 1);
+    }
+    assert!(m.guide_card(8).is_none());
+}
+
+#[test]
+fn test_guide_card() {
+    let m = mitchell(3, 2, EvenTables::Skip).unwrap();
+    let card = m.guide_card(5).unwrap();
+    assert!(card.to_string() == "Pair 5\n\
+                                 Round 1: table 2 EW v 2, boards 3-4\n\
+                                 Round 2: table 3 EW v 3, boards 1-2\n\
+                                 Round 3: table 1 EW v 1, boards 5-6\n");
+    assert!(board_list(&[1, 2, 3, 7, 9, 10]) == "1-3, 7, 9-10");
+}
+
+#[test]
+fn test_read_movement() {
+    let text = "# Three table Mitchell\n\
+                boards 2\n\
+                round 1-4/1 2-5/2 3-6/3\n\
+                \n\
+                round 1-6/2 2-4/3 3-5/1\n\
+                round 1-5/3 2-6/1 3-4/2\n";
+    let m = read_movement(text.as_bytes()).unwrap();
+    assert!(m.tables() == 3 && m.boards_per_round() == 2);
+    assert!(m.find(2, 2).unwrap().ew_pair == 6);
+    assert!(m.rounds() == mitchell(3, 2, EvenTables::Skip).unwrap().rounds());
+
+    let sit_out = "boards 1\nround 1-2/1 -\nround - 1-2/2\n";
+    let m = read_movement(sit_out.as_bytes()).unwrap();
+    assert!(m.tables() == 2 && m.rounds()[1][0].table == 2);
+
+    match read_movement("round 1-2/1\n".as_bytes()) {
+        Err(MovementError::BadLine(1, _)) => {},
+        _ => panic!(),
+    }
+    match read_movement("boards 2\nround 1-2/1 3-4\n".as_bytes()) {
+        Err(MovementError::BadLine(2, _)) => {},
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn test_validate() {
+    let check = |text : &str| {
+        read_movement(text.as_bytes()).err().map(|e| e.to_string())
+    };
+    assert!(check("boards 2\nround 1-2/1 1-3/2\n").unwrap()
+            == "Round 1: pair 1 is in two places");
+    assert!(check("boards 2\nround 1-2/1\nround 1-3/1\n").unwrap()
+            == "Pair 1 plays board 1 twice");
+    // Set 2 is only played once.
+    assert!(check("boards 2\nround 1-2/1 3-4/2\nround 5-6/1\n").unwrap()
+            == "Board 3 is played 1 times, not 2");
+}
@@ -0,0 +1,243 @@
+use std::fmt;
+
+use contract::{Contract, ContractDoubled, ContractNumber, Seat, Side};
+use dds::{DdTable, STRAINS};
+use score::Score;
+
+// Par: the result when both sides bid to their best advantage over a
+// double-dummy table, so that neither can gain by bidding on or by
+// sacrificing. Contracts that go down are doubled.
+
+const LEVELS : [ContractNumber; 7] =
+    [ContractNumber::One, ContractNumber::Two, ContractNumber::Three,
+     ContractNumber::Four, ContractNumber::Five, ContractNumber::Six,
+     ContractNumber::SEVEN];
+
+// Every bid from 1C to 7NT.
+const BIDS : usize = 35;
+
+const SIDES : [Side; 2] = [Side::NorthSouth, Side::EastWest];
+
+fn side_index(side : Side) -> usize {
+    match side {
+        Side::NorthSouth => 0,
+        Side::EastWest   => 1,
+    }
+}
+
+fn seats(side : Side) -> [Seat; 2] {
+    match side {
+        Side::NorthSouth => [Seat::North, Seat::South],
+        Side::EastWest   => [Seat::East, Seat::West],
+    }
+}
+
+// The better of two North-South scores for `side`.
+fn better(side : Side, a : i32, b : i32) -> i32 {
+    match side {
+        Side::NorthSouth => a.max(b),
+        Side::EastWest   => a.min(b),
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ParContract {
+    pub contract : Contract,
+    // Everyone on the declaring side who takes the most tricks.
+    pub declarers : Vec<Seat>,
+    // Overtricks, or undertricks when negative.
+    pub margin : i32,
+}
+
+// Like "4S+1 NS" or "5DX-3 E".
+impl fmt::Display for ParContract {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.contract)?;
+        if self.margin != 0 {
+            write!(f, "{:+}", self.margin)?;
+        }
+        write!(f, " ")?;
+        for seat in &self.declarers {
+            write!(f, "{}", seat)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Par {
+    // From North-South's point of view.
+    pub score : i32,
+    // The lowest par contract in each strain for each side. Empty when the
+    // board is passed out.
+    pub contracts : Vec<ParContract>,
+}
+
+struct Board<'a> {
+    table_ : &'a DdTable,
+    ns_vulnerable_ : bool,
+    ew_vulnerable_ : bool,
+}
+
+impl<'a> Board<'a> {
+    fn contract(&self, bid : usize, side : Side) -> ParContract {
+        let (number, strain) = (LEVELS[bid / 5], STRAINS[bid % 5]);
+        let tricks : Vec<u8> = seats(side).iter()
+            .map(|&s| self.table_.tricks(s, strain)).collect();
+        let most = *tricks.iter().max().unwrap();
+        let margin = most as i32 - number.into_i32() - 6;
+        let doubled = if margin < 0 {
+            ContractDoubled::Doubled
+        } else {
+            ContractDoubled::Undoubled
+        };
+        ParContract {
+            contract : Contract::new(strain, number, doubled),
+            declarers : seats(side).iter().zip(tricks.iter())
+                .filter(|&(_, &t)| t == most).map(|(&s, _)| s).collect(),
+            margin,
+        }
+    }
+
+    // North-South's score when `side` plays `bid`.
+    fn score(&self, bid : usize, side : Side) -> i32 {
+        let c = self.contract(bid, side);
+        let vulnerable = match side {
+            Side::NorthSouth => self.ns_vulnerable_,
+            Side::EastWest   => self.ew_vulnerable_,
+        };
+        let score = Score::from_contract(&c.contract, vulnerable)
+            .score_result(c.margin);
+        match side {
+            Side::NorthSouth => score,
+            Side::EastWest   => -score,
+        }
+    }
+}
+
+// The dealer's side gets the first chance to bid, which only matters when
+// both sides can make the same contract.
+pub fn par(table : &DdTable, vulnerability : (bool, bool), dealer : Seat)
+    -> Par {
+    let board = Board {
+        table_ : table,
+        ns_vulnerable_ : vulnerability.0,
+        ew_vulnerable_ : vulnerability.1,
+    };
+    // The score once a side has bid, with both sides bidding on as long as
+    // it helps them.
+    let mut outcome = [[0; 2]; BIDS];
+    for bid in (0..BIDS).rev() {
+        for &side in &SIDES {
+            let other = side.opponents();
+            outcome[bid][side_index(side)] = (bid + 1..BIDS)
+                .fold(board.score(bid, side), |best, next| {
+                    better(other, best, outcome[next][side_index(other)])
+                });
+        }
+    }
+    let open = |side : Side, pass : i32| {
+        outcome.iter().fold(pass, |best, o| {
+            better(side, best, o[side_index(side)])
+        })
+    };
+    let first = dealer.side();
+    let second = first.opponents();
+    // Either side can still open after the other has passed.
+    let score = open(first, open(second, open(first, 0)));
+
+    // Par contracts are the ones the opponents can't beat with any higher
+    // contract of their own, even one that would then be outbid. A cheaper
+    // sacrifice is no good if they could have bid game over it instead.
+    let mut contracts : Vec<ParContract> = Vec::new();
+    for bid in 0..BIDS {
+        for &side in &SIDES {
+            let other = side.opponents();
+            let beaten = (bid + 1..BIDS).any(|next| {
+                better(other, score, board.score(next, other)) != score
+            });
+            if board.score(bid, side) != score || beaten {
+                continue;
+            }
+            let c = board.contract(bid, side);
+            let lower = contracts.iter().any(|p| {
+                p.contract.suit == c.contract.suit &&
+                    p.declarers[0].side() == side
+            });
+            if !lower {
+                contracts.push(c);
+            }
+        }
+    }
+    Par {
+        score,
+        contracts,
+    }
+}
+
+// Tricks for each seat in the order clubs, diamonds, hearts, spades and
+// notrump, with the partners taking the same.
+#[cfg(test)]
+fn table(ns : [u8; 5], ew : [u8; 5]) -> DdTable {
+    DdTable::new([ns, ew, ns, ew])
+}
+
+#[cfg(test)]
+fn contracts(par : &Par) -> Vec<String> {
+    par.contracts.iter().map(|c| c.to_string()).collect()
+}
+
+#[test]
+fn test_game() {
+    let t = table([6, 5, 7, 10, 8], [6, 8, 6, 3, 5]);
+    let p = par(&t, (false, false), Seat::North);
+    assert!(p.score == 420);
+    assert!(contracts(&p) == vec!["4S NS"]);
+    // A notrump trick more is worth more than the spade game.
+    let t = table([6, 5, 7, 10, 10], [6, 6, 6, 3, 3]);
+    let p = par(&t, (false, false), Seat::East);
+    assert!(p.score == 430);
+    assert!(contracts(&p) == vec!["3NT+1 NS"]);
+}
+
+#[test]
+fn test_sacrifice() {
+    // Three down doubled not vulnerable is cheaper than a vulnerable game.
+    let t = table([6, 5, 7, 10, 8], [6, 8, 6, 3, 5]);
+    let p = par(&t, (true, false), Seat::North);
+    assert!(p.score == 500);
+    assert!(contracts(&p) == vec!["5DX-3 EW"]);
+    // Vulnerable, it costs more than the game.
+    let p = par(&t, (true, true), Seat::North);
+    assert!(p.score == 620);
+    assert!(contracts(&p) == vec!["4S NS"]);
+    // Over 3NT a cheap diamond sacrifice would be left open, so NS bid past
+    // it straight away.
+    let t = table([6, 5, 7, 10, 10], [6, 8, 6, 3, 3]);
+    let p = par(&t, (false, false), Seat::North);
+    assert!(p.score == 430);
+    assert!(contracts(&p) == vec!["4NT NS"]);
+}
+
+#[test]
+fn test_declarer() {
+    let t = DdTable::new([[5, 5, 5, 5, 9], [4, 4, 4, 4, 4],
+                          [5, 5, 5, 5, 8], [4, 4, 4, 4, 4]]);
+    let p = par(&t, (false, false), Seat::West);
+    assert!(p.score == 400);
+    assert!(contracts(&p) == vec!["3NT N"]);
+}
+
+#[test]
+fn test_dealer() {
+    // Whoever bids first makes 1NT.
+    let t = table([6, 6, 6, 6, 7], [6, 6, 6, 6, 7]);
+    let p = par(&t, (false, false), Seat::South);
+    assert!(p.score == 90);
+    assert!(contracts(&p) == vec!["1NT NS"]);
+    let p = par(&t, (false, false), Seat::West);
+    assert!(p.score == -90);
+    assert!(contracts(&p) == vec!["1NT EW"]);
+    let p = par(&table([6; 5], [6; 5]), (false, false), Seat::North);
+    assert!(p.score == 0 && p.contracts.is_empty());
+}
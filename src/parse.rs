@@ -1,6 +1,11 @@
+use std::fmt;
 use std::str::FromStr;
 
-use contract::{Contract, ContractNumber, ContractSuit, ContractDoubled};
+use auction::Call;
+use cards::{Card, Deal, Hand, Rank, Suit, SUITS};
+use contract::{Contract, ContractNumber, ContractSuit, ContractDoubled, Seat};
+use imp::Room;
+use score::{Honors, HonorsKind, ScoringMode};
 
 // The idea here is to let the frontend know where to start marking the
 // contract name as invalid. `Incomplete` does not count as invalid if you
@@ -13,6 +18,21 @@ pub enum ContractParseError {
     InvalidTrailing(usize, char),
 }
 
+impl fmt::Display for ContractParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ContractParseError::Incomplete =>
+                write!(f, "Incomplete contract"),
+            ContractParseError::InvalidNumber(c) =>
+                write!(f, "Invalid level: {}", c),
+            ContractParseError::InvalidSuit(ref s) =>
+                write!(f, "Invalid suit: {}", s),
+            ContractParseError::InvalidTrailing(_, c) =>
+                write!(f, "Invalid trailing: {}", c),
+        }
+    }
+}
+
 impl FromStr for Contract {
     type Err = ContractParseError;
     fn from_str(name : &str) -> Result<Contract, ContractParseError> {
@@ -78,7 +98,8 @@ impl FromStr for Contract {
 }
 
 // Only use directly in testing
-fn parse_contract(name : &str) -> Result<Contract, ContractParseError> {
+#[cfg(test)]
+pub fn parse_contract(name : &str) -> Result<Contract, ContractParseError> {
     name.parse()
 }
 
@@ -96,6 +117,182 @@ pub fn parse_input(input : &str) -> Result<Contract, ContractParseError> {
     input.to_uppercase().parse()
 }
 
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum HonorsParseError {
+    Incomplete,
+    InvalidHonors(char),
+    InvalidTrailing(char),
+}
+
+impl fmt::Display for HonorsParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            HonorsParseError::Incomplete        =>
+                write!(f, "Incomplete honors"),
+            HonorsParseError::InvalidHonors(c)   =>
+                write!(f, "Invalid honors: {}", c),
+            HonorsParseError::InvalidTrailing(c) =>
+                write!(f, "Invalid trailing: {}", c),
+        }
+    }
+}
+
+// "4", "5" or "A" for the declaring side, with a leading "-" when the
+// defenders hold them.
+impl FromStr for Honors {
+    type Err = HonorsParseError;
+    fn from_str(name : &str) -> Result<Honors, HonorsParseError> {
+        let mut chars = name.chars().peekable();
+        let declarer_side = if chars.peek() == Some(&'-') {
+            chars.next();
+            false
+        } else {
+            true
+        };
+        let kind = match chars.next() {
+            Some('4') => HonorsKind::FourTrump,
+            Some('5') => HonorsKind::FiveTrump,
+            Some('A') => HonorsKind::FourAces,
+            Some(c)   => return Err(HonorsParseError::InvalidHonors(c)),
+            None      => return Err(HonorsParseError::Incomplete),
+        };
+        if let Some(c) = chars.next() {
+            return Err(HonorsParseError::InvalidTrailing(c));
+        }
+        Ok(Honors::new(kind, declarer_side))
+    }
+}
+
+// An empty string clears the honors.
+pub fn parse_honors(input : &str) -> Result<Option<Honors>, HonorsParseError> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Ok(None);
+    }
+    input.to_uppercase().parse().map(Some)
+}
+
+// The single letters that `Seat` displays as.
+impl FromStr for Seat {
+    type Err = String;
+    fn from_str(name : &str) -> Result<Seat, String> {
+        match name {
+            "N" => Ok(Seat::North),
+            "E" => Ok(Seat::East),
+            "S" => Ok(Seat::South),
+            "W" => Ok(Seat::West),
+            _   => Err(format!("Invalid seat: {}", name)),
+        }
+    }
+}
+
+impl FromStr for ScoringMode {
+    type Err = String;
+    fn from_str(name : &str) -> Result<ScoringMode, String> {
+        match name {
+            "Duplicate" => Ok(ScoringMode::Duplicate),
+            "Rubber"    => Ok(ScoringMode::Rubber),
+            "Chicago"   => Ok(ScoringMode::Chicago),
+            _           => Err(format!("Invalid scoring mode: {}", name)),
+        }
+    }
+}
+
+// "P", "X" and "XX" as `Call` displays them, also "Pass", "D" and "R"
+// as in PBN and LIN. Bids can drop the T from "NT".
+impl FromStr for Call {
+    type Err = String;
+    fn from_str(name : &str) -> Result<Call, String> {
+        let name = name.to_uppercase();
+        match name.as_str() {
+            "P" | "PASS"        => return Ok(Call::Pass),
+            "X" | "D" | "DBL"   => return Ok(Call::Double),
+            "XX" | "R" | "RDBL" => return Ok(Call::Redouble),
+            _ => (),
+        }
+        let bid = if name.len() == 2 && name.ends_with('N') {
+            format!("{}T", name)
+        } else {
+            name
+        };
+        match bid.parse::<Contract>() {
+            Ok(Contract { number, suit, doubled : ContractDoubled::Undoubled })
+                => Ok(Call::Bid(number, suit)),
+            _   => Err(format!("Invalid call: {}", bid)),
+        }
+    }
+}
+
+// Suit then rank, as `Card` displays.
+impl FromStr for Card {
+    type Err = String;
+    fn from_str(name : &str) -> Result<Card, String> {
+        let mut chars = name.chars();
+        match (chars.next().and_then(Suit::from_char),
+               chars.next().and_then(Rank::from_char), chars.next()) {
+            (Some(suit), Some(rank), None) => Ok(Card::new(suit, rank)),
+            _ => Err(format!("Invalid card: {}", name)),
+        }
+    }
+}
+
+// PBN holdings from spades down, like "AKQ.T98..5432". A lone "-" is an
+// unknown hand and reads as empty.
+impl FromStr for Hand {
+    type Err = String;
+    fn from_str(text : &str) -> Result<Hand, String> {
+        let mut hand = Hand::new();
+        if text == "-" {
+            return Ok(hand);
+        }
+        let holdings : Vec<&str> = text.split('.').collect();
+        if holdings.len() != 4 {
+            return Err(format!("Invalid hand: {}", text));
+        }
+        for (&suit, holding) in SUITS.iter().rev().zip(holdings) {
+            for c in holding.chars() {
+                let rank = Rank::from_char(c)
+                    .ok_or_else(|| format!("Invalid rank: {}", c))?;
+                if !hand.insert(Card::new(suit, rank)) {
+                    return Err(format!("Repeated card: {}{}", suit, rank));
+                }
+            }
+        }
+        Ok(hand)
+    }
+}
+
+// A PBN deal: the first seat, a colon, then four hands clockwise from it.
+// Use `Deal::validate` to check that it is complete.
+impl FromStr for Deal {
+    type Err = String;
+    fn from_str(text : &str) -> Result<Deal, String> {
+        let mut parts = text.trim().splitn(2, ':');
+        let mut seat : Seat = parts.next().unwrap_or("").parse()?;
+        let hands : Vec<&str> =
+            parts.next().unwrap_or("").split_whitespace().collect();
+        if hands.len() != 4 {
+            return Err(format!("Expected four hands: {}", text));
+        }
+        let mut deal = Deal::new();
+        for hand in hands {
+            *deal.hand_mut(seat) = hand.parse()?;
+            seat = seat.next();
+        }
+        Ok(deal)
+    }
+}
+
+// "A" or "O" for the open room, "B" or "C" for the closed room. Anything
+// else clears it.
+pub fn parse_room(input : &str) -> Option<Room> {
+    match input.trim().to_uppercase().as_str() {
+        "A" | "O" => Some(Room::Open),
+        "B" | "C" => Some(Room::Closed),
+        _         => None,
+    }
+}
+
 #[cfg(test)]
 fn check_contract(name : &str) {
     assert!(parse_contract(name).unwrap().to_string() == name);
@@ -141,3 +338,13 @@ fn test_4nty() {
 fn test_4ntxxx() {
     check_err("4NTXXX", ContractParseError::InvalidTrailing(5, 'X'));
 }
+
+#[test]
+fn test_honors() {
+    assert!(parse_honors("-a").unwrap().unwrap().to_string() == "-A");
+    assert!(parse_honors("5").unwrap() ==
+            Some(Honors::new(HonorsKind::FiveTrump, true)));
+    assert!(parse_honors("").unwrap().is_none());
+    assert!(parse_honors("3").unwrap_err() ==
+            HonorsParseError::InvalidHonors('3'));
+}
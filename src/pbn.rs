@@ -0,0 +1,342 @@
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+use cards::Deal;
+use contract::{Seat, SEATS};
+use data::{Entry, Table};
+use imp::Room;
+use parse::parse_input;
+use score::ScoringMode;
+
+// Portable Bridge Notation: one game per entry, each a block of
+// `[Tag "value"]` lines separated by blank lines.
+
+#[derive(Debug)]
+pub enum PbnError {
+    Io(io::Error),
+    // Line number and what was wrong with it.
+    BadLine(usize, String),
+}
+
+impl fmt::Display for PbnError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PbnError::Io(ref e)         => write!(f, "{}", e),
+            PbnError::BadLine(n, ref e) => write!(f, "Line {}: {}", n, e),
+        }
+    }
+}
+
+impl From<io::Error> for PbnError {
+    fn from(e : io::Error) -> PbnError {
+        PbnError::Io(e)
+    }
+}
+
+fn seat_tag(seat : Seat) -> &'static str {
+    match seat {
+        Seat::North => "North",
+        Seat::East  => "East",
+        Seat::South => "South",
+        Seat::West  => "West",
+    }
+}
+
+pub fn vulnerability_tag(vulnerability : (bool, bool)) -> &'static str {
+    match vulnerability {
+        (false, false) => "None",
+        (true,  false) => "NS",
+        (false, true)  => "EW",
+        (true,  true)  => "All",
+    }
+}
+
+// Also takes the older "Love" and "Both" spellings.
+pub fn parse_vulnerability(tag : &str) -> Option<(bool, bool)> {
+    match tag {
+        "None" | "Love" | "-" => Some((false, false)),
+        "NS"                  => Some((true, false)),
+        "EW"                  => Some((false, true)),
+        "All" | "Both"        => Some((true, true)),
+        _                     => None,
+    }
+}
+
+fn escape(value : &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn write_tag<W : Write>(out : &mut W, tag : &str, value : &str)
+    -> io::Result<()> {
+    writeln!(out, "[{} \"{}\"]", tag, escape(value))
+}
+
+pub fn write_pbn<W : Write>(out : &mut W, event : &str, table : &Table,
+                            entries : &[Entry]) -> io::Result<()> {
+    for (ix, entry) in entries.iter().enumerate() {
+        if ix > 0 {
+            writeln!(out)?;
+        }
+        write_tag(out, "Event", event)?;
+        write_tag(out, "Board", &entry.board_num().to_string())?;
+        for &seat in &SEATS {
+            write_tag(out, seat_tag(seat), table.get_player(seat))?;
+        }
+        let dealer = entry.mode().dealer(entry.board_num());
+        write_tag(out, "Dealer", &dealer.to_string())?;
+        write_tag(out, "Vulnerable",
+                  vulnerability_tag(entry.vulnerability()))?;
+        if let Some(deal) = entry.deal() {
+            write_tag(out, "Deal", &deal.to_pbn(dealer))?;
+        }
+        if let Some(room) = entry.room() {
+            write_tag(out, "Room", match room {
+                Room::Open   => "Open",
+                Room::Closed => "Closed",
+            })?;
+        }
+        let declarer = entry.declarer().map(|d| d.to_string());
+        write_tag(out, "Declarer", declarer.as_deref().unwrap_or(""))?;
+        match entry.contract() {
+            Some(c) => {
+                write_tag(out, "Contract", &c.to_string())?;
+                if let Some(r) = entry.result() {
+                    let tricks = 6 + c.number.into_i32() + r;
+                    write_tag(out, "Result", &tricks.to_string())?;
+                }
+            },
+            None => write_tag(out, "Contract", "")?,
+        }
+        if let Some(score) = entry.ns_score() {
+            write_tag(out, "Score", &format!("NS {}", score))?;
+        }
+    }
+    Ok(())
+}
+
+// Hand records for boards numbered from 1, with duplicate dealers and
+// vulnerability.
+pub fn write_deals<W : Write>(out : &mut W, event : &str, deals : &[Deal])
+    -> io::Result<()> {
+    for (ix, deal) in deals.iter().enumerate() {
+        let board = ix as u32 + 1;
+        if ix > 0 {
+            writeln!(out)?;
+        }
+        let dealer = ScoringMode::Duplicate.dealer(board);
+        write_tag(out, "Event", event)?;
+        write_tag(out, "Board", &board.to_string())?;
+        write_tag(out, "Dealer", &dealer.to_string())?;
+        write_tag(out, "Vulnerable", vulnerability_tag(
+            ScoringMode::Duplicate.vulnerability(board)))?;
+        write_tag(out, "Deal", &deal.to_pbn(dealer))?;
+    }
+    Ok(())
+}
+
+// Returns the tag name and value of a `[Tag "value"]` line.
+fn parse_tag(line : &str) -> Option<(&str, String)> {
+    let inner = line.strip_prefix('[')?.strip_suffix(']')?;
+    let (name, rest) = inner.split_at(inner.find(' ')?);
+    let quoted = rest.trim().strip_prefix('"')?.strip_suffix('"')?;
+    let mut value = String::new();
+    let mut chars = quoted.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => value.extend(chars.next()),
+            _    => value.push(c),
+        }
+    }
+    Some((name, value))
+}
+
+#[derive(Default)]
+struct Game {
+    tags : Vec<(String, String)>,
+    // Line the game started on, for errors.
+    line : usize,
+}
+
+impl Game {
+    fn tag(&self, name : &str) -> Option<&str> {
+        self.tags.iter().find(|t| t.0 == name).map(|t| t.1.as_str())
+            .filter(|v| !v.is_empty())
+    }
+
+    // Passed-out boards and games without a declarer give no entry.
+    fn build(&self, table : &Table) -> Result<Option<Entry>, String> {
+        let board = match self.tag("Board") {
+            Some(b) =>
+                b.parse().map_err(|_| format!("Invalid board: {}", b))?,
+            None    => return Err(String::from("Game without a board")),
+        };
+        let declarer : Seat = match self.tag("Declarer") {
+            Some(d) => d.parse()?,
+            None    => return Ok(None),
+        };
+        let contract = match self.tag("Contract") {
+            Some("Pass") | None => return Ok(None),
+            Some(c) => parse_input(c)
+                .map_err(|e| format!("Invalid contract {}: {:?}", c, e))?,
+        };
+        let mut entry = Entry::new(table, declarer, board);
+        if let Some(v) = self.tag("Vulnerable") {
+            let (ns, ew) = parse_vulnerability(v)
+                .ok_or_else(|| format!("Invalid vulnerability: {}", v))?;
+            entry.set_vulnerability(ns, ew);
+        }
+        entry.set_contract(contract);
+        if let Some(r) = self.tag("Result") {
+            let tricks : i32 =
+                r.parse().map_err(|_| format!("Invalid result: {}", r))?;
+            entry.record(tricks - 6 - contract.number.into_i32());
+        }
+        if let Some(d) = self.tag("Deal") {
+            let deal : Deal = d.parse()?;
+            entry.set_deal(Some(deal));
+        }
+        match self.tag("Room") {
+            Some("Open")   => entry.set_room(Some(Room::Open)),
+            Some("Closed") => entry.set_room(Some(Room::Closed)),
+            _ => (),
+        }
+        Ok(Some(entry))
+    }
+}
+
+// Player names come from the first game that has them.
+pub fn read_pbn<R : BufRead>(input : R)
+    -> Result<(Table, Vec<Entry>), PbnError> {
+    let mut games = Vec::new();
+    let mut current = Game::default();
+    let mut in_comment = false;
+    for (n, line) in input.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if in_comment {
+            in_comment = !line.contains('}');
+            continue;
+        }
+        if line.starts_with('{') {
+            in_comment = !line.contains('}');
+            continue;
+        }
+        if line.is_empty() {
+            if !current.tags.is_empty() {
+                games.push(current);
+                current = Game::default();
+            }
+            continue;
+        }
+        // Comments, escapes, and the bodies of sections like [Auction].
+        if line.starts_with('%') || line.starts_with(';') ||
+            !line.starts_with('[') {
+            continue;
+        }
+        let (name, value) = parse_tag(line).ok_or_else(
+            || PbnError::BadLine(n + 1, format!("Invalid tag: {}", line)))?;
+        if current.tags.is_empty() {
+            current.line = n + 1;
+        }
+        current.tags.push((String::from(name), value));
+    }
+    if !current.tags.is_empty() {
+        games.push(current);
+    }
+
+    let mut table = Table::new();
+    if let Some(game) = games.iter().find(|g| g.tag("North").is_some()) {
+        for &seat in &SEATS {
+            if let Some(name) = game.tag(seat_tag(seat)) {
+                table.set_player(seat, name);
+            }
+        }
+    }
+    let mut entries = Vec::new();
+    for game in &games {
+        if let Some(entry) = game.build(&table)
+            .map_err(|e| PbnError::BadLine(game.line, e))? {
+            entries.push(entry);
+        }
+    }
+    Ok((table, entries))
+}
+
+#[test]
+fn test_round_trip() {
+    let mut table = Table::new();
+    table.set_player(Seat::South, "Jo \"Ace\" Smith");
+    let mut first = Entry::new(&table, Seat::South, 2);
+    first.set_contract(parse_input("4sx").unwrap());
+    first.record(-2);
+    first.set_room(Some(Room::Closed));
+    let deal : Deal = "N:AKQJ.T98.765.432 T987.654.32.AKQJ \
+                       65432.AKQ.J4.T98 .J732.AKQT98.765".parse().unwrap();
+    first.set_deal(Some(deal));
+    let mut second = Entry::new(&table, Seat::West, 3);
+    second.set_contract(parse_input("3nt").unwrap());
+    second.record(1);
+
+    let mut out = Vec::new();
+    write_pbn(&mut out, "Club night", &table, &[first, second]).unwrap();
+    let text = String::from_utf8(out.clone()).unwrap();
+    assert!(text.contains("[Vulnerable \"NS\"]"));
+    assert!(text.contains("[Result \"8\"]"));
+    assert!(text.contains("[Score \"NS -500\"]"));
+    assert!(text.contains("[Dealer \"E\"]"));
+    assert!(text.contains("[Deal \"E:T987.654.32.AKQJ "));
+
+    let (table, entries) = read_pbn(&out[..]).unwrap();
+    assert!(table.get_player(Seat::South) == "Jo \"Ace\" Smith");
+    assert!(entries.len() == 2);
+    assert!(entries[0].value() == Some(-500));
+    assert!(entries[0].room() == Some(Room::Closed));
+    assert!(entries[0].deal() == Some(&deal));
+    assert!(entries[1].deal().is_none());
+    assert!(entries[1].board_num() == 3);
+    assert!(entries[1].vulnerability() == (false, true));
+    assert!(entries[1].value() == Some(630));
+}
+
+#[test]
+fn test_hand_records() {
+    let deal : Deal = "N:AKQJ.T98.765.432 T987.654.32.AKQJ \
+                       65432.AKQ.J4.T98 .J732.AKQT98.765".parse().unwrap();
+    let mut out = Vec::new();
+    write_deals(&mut out, "Seed 1", &[deal, deal]).unwrap();
+    let text = String::from_utf8(out.clone()).unwrap();
+    assert!(text.contains("[Board \"2\"]\n[Dealer \"E\"]"));
+    assert!(text.contains("[Deal \"E:T987.654.32.AKQJ "));
+    // Nothing has been played yet, so there is nothing to score.
+    let (_, entries) = read_pbn(&out[..]).unwrap();
+    assert!(entries.is_empty());
+}
+
+#[test]
+fn test_import() {
+    let text = "% PBN 2.1\n\
+                [Event \"\"]\n\
+                [Board \"1\"]\n\
+                [Dealer \"N\"]\n\
+                [Vulnerable \"Both\"]\n\
+                [Declarer \"E\"]\n\
+                [Contract \"2H\"]\n\
+                [Result \"9\"]\n\
+                [Auction \"N\"]\n\
+                Pass 1H Pass 2H\n\
+                Pass Pass Pass\n\
+                {A comment\n\
+                spanning lines}\n\
+                \n\
+                [Board \"2\"]\n\
+                [Declarer \"\"]\n\
+                [Contract \"Pass\"]\n";
+    let (_, entries) = read_pbn(text.as_bytes()).unwrap();
+    assert!(entries.len() == 1);
+    assert!(entries[0].is_vulnerable());
+    assert!(entries[0].result() == Some(1));
+    assert!(entries[0].value() == Some(140));
+
+    assert!(matches!(read_pbn(&b"[Board \"x\"]\n"[..]),
+                     Err(PbnError::BadLine(1, _))));
+}
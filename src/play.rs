@@ -0,0 +1,259 @@
+use std::fmt;
+
+use cards::{Card, Deal, DealError, Hand, Suit};
+use contract::{Contract, Seat};
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum PlayError {
+    // Play needs a full deal and a contract to check against.
+    NoDeal,
+    NoContract,
+    // Every trick has been played or claimed.
+    Finished,
+    // Not in the hand of the player whose turn it is.
+    NotHeld(Seat, Card),
+    // Didn't follow suit while holding a card of the suit led.
+    Revoke(Seat, Card),
+    // Fewer tricks than declarer already has, or more than are left.
+    BadClaim(u32),
+}
+
+impl fmt::Display for PlayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PlayError::NoDeal     => write!(f, "No deal to play"),
+            PlayError::NoContract => write!(f, "No contract to play"),
+            PlayError::Finished   => write!(f, "Play is over"),
+            PlayError::NotHeld(seat, card) =>
+                write!(f, "{} doesn't hold {}", seat, card),
+            PlayError::Revoke(seat, card) =>
+                write!(f, "{} has to follow suit, not play {}", seat, card),
+            PlayError::BadClaim(n) => write!(f, "Can't claim {} tricks", n),
+        }
+    }
+}
+
+// The seat that wins a complete trick led by `leader`.
+pub fn trick_winner(leader : Seat, cards : &[Card], trump : Option<Suit>)
+    -> Seat {
+    let mut best = (leader, cards[0]);
+    let mut seat = leader;
+    for &card in &cards[1..] {
+        seat = seat.next();
+        let beats = if card.suit == best.1.suit {
+            card.rank > best.1.rank
+        } else {
+            Some(card.suit) == trump
+        };
+        if beats {
+            best = (seat, card);
+        }
+    }
+    best.0
+}
+
+// The cards played on one board, starting with the opening lead. Only legal
+// cards are ever added, so the deal always says who played what.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Play {
+    deal_ : Deal,
+    contract_ : Contract,
+    declarer_ : Seat,
+    cards_ : Vec<Card>,
+    // Declarer's tricks in all, once the rest have been claimed.
+    claim_ : Option<u32>,
+}
+
+impl Play {
+    pub fn new(deal : Deal, contract : Contract, declarer : Seat)
+        -> Result<Play, DealError> {
+        deal.validate()?;
+        Ok(Play {
+            deal_ : deal,
+            contract_ : contract,
+            declarer_ : declarer,
+            cards_ : Vec::new(),
+            claim_ : None,
+        })
+    }
+
+    pub fn deal(&self) -> &Deal {
+        &self.deal_
+    }
+
+    pub fn contract(&self) -> Contract {
+        self.contract_
+    }
+
+    pub fn declarer(&self) -> Seat {
+        self.declarer_
+    }
+
+    pub fn cards(&self) -> &[Card] {
+        &self.cards_
+    }
+
+    pub fn claimed(&self) -> Option<u32> {
+        self.claim_
+    }
+
+    // The winner of every complete trick.
+    pub fn winners(&self) -> Vec<Seat> {
+        let trump = Suit::from_strain(self.contract_.suit);
+        let mut leader = self.declarer_.next();
+        self.cards_.chunks(4).filter(|t| t.len() == 4).map(|trick| {
+            leader = trick_winner(leader, trick, trump);
+            leader
+        }).collect()
+    }
+
+    fn trick(&self) -> &[Card] {
+        &self.cards_[self.cards_.len() / 4 * 4..]
+    }
+
+    // The seat whose turn it is to play.
+    pub fn next_seat(&self) -> Seat {
+        let leader = self.winners().last().cloned()
+            .unwrap_or_else(|| self.declarer_.next());
+        (0..self.trick().len()).fold(leader, |s, _| s.next())
+    }
+
+    // What `seat` has left to play.
+    pub fn hand(&self, seat : Seat) -> Hand {
+        let mut hand = *self.deal_.hand(seat);
+        for &card in &self.cards_ {
+            hand.remove(card);
+        }
+        hand
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.claim_.is_some() || self.cards_.len() == 52
+    }
+
+    pub fn check(&self, card : Card) -> Result<(), PlayError> {
+        if self.is_finished() {
+            return Err(PlayError::Finished);
+        }
+        let seat = self.next_seat();
+        let hand = self.hand(seat);
+        if !hand.contains(card) {
+            return Err(PlayError::NotHeld(seat, card));
+        }
+        match self.trick().first() {
+            Some(led) if card.suit != led.suit && hand.length(led.suit) > 0 =>
+                Err(PlayError::Revoke(seat, card)),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn play(&mut self, card : Card) -> Result<(), PlayError> {
+        self.check(card)?;
+        self.cards_.push(card);
+        Ok(())
+    }
+
+    // Declarer's tricks so far, or all of them once play is over.
+    pub fn declarer_tricks(&self) -> u32 {
+        self.claim_.unwrap_or_else(|| {
+            self.winners().iter()
+                .filter(|s| s.side() == self.declarer_.side()).count() as u32
+        })
+    }
+
+    // Ends play with declarer taking `tricks` in all, counting the ones
+    // already won. A trick in progress is still up for grabs.
+    pub fn claim(&mut self, tricks : u32) -> Result<(), PlayError> {
+        if self.is_finished() {
+            return Err(PlayError::Finished);
+        }
+        let won = self.declarer_tricks();
+        let left = 13 - self.cards_.len() as u32 / 4;
+        if tricks < won || tricks > won + left {
+            return Err(PlayError::BadClaim(tricks));
+        }
+        self.claim_ = Some(tricks);
+        Ok(())
+    }
+
+    // Over or under tricks, once play is over.
+    pub fn result(&self) -> Option<i32> {
+        if !self.is_finished() {
+            return None;
+        }
+        Some(self.declarer_tricks() as i32 - 6 -
+             self.contract_.number.into_i32())
+    }
+}
+
+#[cfg(test)]
+const TEST_DEAL : &str =
+    "N:AKQJ.T98.765.432 T987.654.32.AKQJ 65432.AKQ.J4.T98 .J732.AKQT98.765";
+
+#[cfg(test)]
+fn cards(text : &str) -> Vec<Card> {
+    text.split_whitespace().map(|c| c.parse().unwrap()).collect()
+}
+
+#[cfg(test)]
+fn play(contract : &str, declarer : Seat) -> Play {
+    Play::new(TEST_DEAL.parse().unwrap(), contract.parse().unwrap(),
+              declarer).unwrap()
+}
+
+#[test]
+fn test_trick_winner() {
+    let spades = cards("HK HA S2 H3");
+    assert!(trick_winner(Seat::West, &spades, Some(Suit::Spades))
+            == Seat::East);
+    assert!(trick_winner(Seat::West, &spades, None) == Seat::North);
+    let discards = cards("D4 CA HA D5");
+    assert!(trick_winner(Seat::South, &discards, Some(Suit::Spades))
+            == Seat::East);
+}
+
+#[test]
+fn test_follow_suit() {
+    // South declares in spades, so West leads.
+    let mut p = play("4S", Seat::South);
+    assert!(p.next_seat() == Seat::West);
+    let ace = "SA".parse().unwrap();
+    assert!(p.play(ace) == Err(PlayError::NotHeld(Seat::West, ace)));
+    p.play("DA".parse().unwrap()).unwrap();
+    let club = "C2".parse().unwrap();
+    assert!(p.play(club) == Err(PlayError::Revoke(Seat::North, club)));
+    for card in cards("D5 D2 DJ DK D6 D3 D4") {
+        p.play(card).unwrap();
+    }
+    assert!(p.winners() == vec![Seat::West, Seat::West]);
+    // East and South are out of diamonds, and South ruffs.
+    for card in cards("DQ D7 H4 S2") {
+        p.play(card).unwrap();
+    }
+    assert!(p.winners() == vec![Seat::West, Seat::West, Seat::South]);
+    assert!(p.next_seat() == Seat::South);
+    assert!(p.declarer_tricks() == 1);
+    assert!(p.hand(Seat::South).len() == 10);
+    assert!(p.result().is_none());
+}
+
+#[test]
+fn test_claim() {
+    // North declares, and wins the first trick.
+    let mut p = play("6NT", Seat::North);
+    for card in cards("ST S2 H2 SA") {
+        p.play(card).unwrap();
+    }
+    assert!(p.claim(0) == Err(PlayError::BadClaim(0)));
+    assert!(p.claim(14) == Err(PlayError::BadClaim(14)));
+    // North leads to the second trick before claiming the rest, and the
+    // trick in progress counts as one of them.
+    p.play("C2".parse().unwrap()).unwrap();
+    p.claim(13).unwrap();
+    assert!(p.result() == Some(1));
+    assert!(p.play("CK".parse().unwrap()) == Err(PlayError::Finished));
+
+    let mut p = play("6NT", Seat::North);
+    p.claim(11).unwrap();
+    assert!(p.result() == Some(-1));
+}
@@ -0,0 +1,203 @@
+use contract::{Contract, Side};
+use score::{Honors, Score};
+
+// What a single deal did to the rubber, so the frontend knows when to close
+// the ledger and start a new one.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum DealOutcome {
+    Continues,
+    Game(Side),
+    Rubber(Side),
+}
+
+// Keeps the "above/below the line" ledger for a single rubber.
+//
+// Trick values come from `score::Score`, but none of its duplicate bonuses
+// are used: part-scores carry over toward game, vulnerability comes from
+// games already won, and the game bonus is only paid out as the 500/700
+// rubber bonus.
+pub struct RubberScorer {
+    above_ : [i32; 2],
+    below_ : [i32; 2],
+    part_score_ : [i32; 2],
+    games_ : [u32; 2],
+    winner_ : Option<Side>,
+}
+
+fn ix(side : Side) -> usize {
+    match side {
+        Side::NorthSouth => 0,
+        Side::EastWest   => 1,
+    }
+}
+
+impl RubberScorer {
+    pub fn new() -> RubberScorer {
+        RubberScorer {
+            above_ : [0, 0],
+            below_ : [0, 0],
+            part_score_ : [0, 0],
+            games_ : [0, 0],
+            winner_ : None,
+        }
+    }
+
+    pub fn record(&mut self, declarer : Side, contract : &Contract,
+                  margin : i32) -> DealOutcome {
+        self.record_with_honors(declarer, contract, margin, None)
+    }
+
+    // Recording a deal once the rubber is over is a logic error; start a new
+    // `RubberScorer` instead. Honors go above the line for whoever held
+    // them, and should already have been checked against the contract.
+    pub fn record_with_honors(&mut self, declarer : Side, contract : &Contract,
+                              margin : i32, honors : Option<Honors>)
+        -> DealOutcome {
+        assert!(!self.is_finished(), "Rubber is already over");
+        let defenders = declarer.opponents();
+        if let Some(h) = honors {
+            let holder = if h.declarer_side { declarer } else { defenders };
+            self.above_[ix(holder)] += h.value();
+        }
+        let score = Score::from_contract(contract,
+                                         self.is_vulnerable(declarer));
+        if margin < 0 {
+            self.above_[ix(defenders)] -= score.score_result(margin);
+            return DealOutcome::Continues;
+        }
+
+        let below = score.first_trick_value +
+            (contract.number.into_i32() - 1) * score.trick_value;
+        self.below_[ix(declarer)] += below;
+        self.part_score_[ix(declarer)] += below;
+        self.above_[ix(declarer)] +=
+            score.insult + score.slam_bonus + score.overtricks * margin;
+
+        if self.part_score_[ix(declarer)] < 100 {
+            return DealOutcome::Continues;
+        }
+
+        // A game wipes out both sides' part-scores.
+        self.part_score_ = [0, 0];
+        self.games_[ix(declarer)] += 1;
+        if self.games_[ix(declarer)] < 2 {
+            return DealOutcome::Game(declarer);
+        }
+
+        let rubber_bonus =
+            if self.games_[ix(defenders)] == 0 { 700 } else { 500 };
+        self.above_[ix(declarer)] += rubber_bonus;
+        self.winner_ = Some(declarer);
+        DealOutcome::Rubber(declarer)
+    }
+
+    pub fn is_vulnerable(&self, side : Side) -> bool {
+        self.games_[ix(side)] > 0
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.winner_.is_some()
+    }
+
+    pub fn winner(&self) -> Option<Side> {
+        self.winner_
+    }
+
+    pub fn games(&self, side : Side) -> u32 {
+        self.games_[ix(side)]
+    }
+
+    // Points toward the game currently being played.
+    pub fn part_score(&self, side : Side) -> i32 {
+        self.part_score_[ix(side)]
+    }
+
+    pub fn above_line(&self, side : Side) -> i32 {
+        self.above_[ix(side)]
+    }
+
+    pub fn below_line(&self, side : Side) -> i32 {
+        self.below_[ix(side)]
+    }
+
+    pub fn total(&self, side : Side) -> i32 {
+        self.above_line(side) + self.below_line(side)
+    }
+}
+
+impl Default for RubberScorer {
+    fn default() -> RubberScorer {
+        RubberScorer::new()
+    }
+}
+
+#[cfg(test)]
+use parse::parse_contract;
+#[cfg(test)]
+use score::HonorsKind;
+
+#[cfg(test)]
+fn play(scorer : &mut RubberScorer, side : Side, contract : &str,
+        margin : i32) -> DealOutcome {
+    scorer.record(side, &parse_contract(contract).unwrap(), margin)
+}
+
+#[test]
+fn test_part_scores_carry_to_game() {
+    let mut scorer = RubberScorer::new();
+    assert!(play(&mut scorer, Side::NorthSouth, "2H", 0)
+            == DealOutcome::Continues);
+    assert!(scorer.part_score(Side::NorthSouth) == 60);
+    assert!(play(&mut scorer, Side::NorthSouth, "2D", 0)
+            == DealOutcome::Game(Side::NorthSouth));
+    assert!(scorer.part_score(Side::NorthSouth) == 0);
+    assert!(scorer.below_line(Side::NorthSouth) == 100);
+    assert!(scorer.is_vulnerable(Side::NorthSouth));
+    assert!(!scorer.is_vulnerable(Side::EastWest));
+}
+
+#[test]
+fn test_game_wipes_opponent_part_score() {
+    let mut scorer = RubberScorer::new();
+    play(&mut scorer, Side::EastWest, "3C", 0);
+    play(&mut scorer, Side::NorthSouth, "4S", 1);
+    assert!(scorer.part_score(Side::EastWest) == 0);
+    assert!(scorer.below_line(Side::EastWest) == 60);
+    assert!(scorer.above_line(Side::NorthSouth) == 30);
+}
+
+#[test]
+fn test_two_nil_rubber() {
+    let mut scorer = RubberScorer::new();
+    play(&mut scorer, Side::NorthSouth, "3NT", 0);
+    assert!(play(&mut scorer, Side::NorthSouth, "4H", 0)
+            == DealOutcome::Rubber(Side::NorthSouth));
+    assert!(scorer.is_finished());
+    assert!(scorer.total(Side::NorthSouth) == 100 + 120 + 700);
+}
+
+#[test]
+fn test_two_one_rubber_with_penalties() {
+    let mut scorer = RubberScorer::new();
+    play(&mut scorer, Side::NorthSouth, "3NT", 0);
+    play(&mut scorer, Side::EastWest, "5D", 0);
+    // Vulnerable, doubled, down two goes to the defenders.
+    play(&mut scorer, Side::NorthSouth, "4SX", -2);
+    assert!(scorer.above_line(Side::EastWest) == 500);
+    assert!(play(&mut scorer, Side::EastWest, "4H", 0)
+            == DealOutcome::Rubber(Side::EastWest));
+    assert!(scorer.above_line(Side::EastWest) == 500 + 500);
+    assert!(scorer.total(Side::EastWest) == 1000 + 100 + 120);
+    assert!(scorer.total(Side::NorthSouth) == 100);
+}
+
+#[test]
+fn test_defenders_honors() {
+    let mut scorer = RubberScorer::new();
+    let honors = Honors::new(HonorsKind::FourTrump, false);
+    scorer.record_with_honors(Side::NorthSouth,
+                              &parse_contract("4S").unwrap(), -1,
+                              Some(honors));
+    assert!(scorer.above_line(Side::EastWest) == 150);
+    assert!(scorer.above_line(Side::NorthSouth) == 0);
+}
@@ -0,0 +1,458 @@
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use contract::{Seat, SEATS};
+use data::{Entry, Table};
+use history::{Edit, History};
+use parse::{parse_honors, parse_input, parse_room};
+use pbn::{parse_vulnerability, vulnerability_tag};
+use score::ScoringMode;
+
+// A line-based format: a version header, the session settings, then one
+// block per entry. Keys never change meaning between versions; new ones
+// may be added.
+const HEADER : &str = "brodg-session 1";
+
+pub struct SavedSession {
+    pub table : Table,
+    pub mode : ScoringMode,
+    pub dealer : Seat,
+    pub entries : Vec<Entry>,
+    pub history : History,
+}
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io(io::Error),
+    BadHeader,
+    // Line number and what was wrong with it.
+    BadLine(usize, String),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LoadError::Io(ref e)          => write!(f, "{}", e),
+            LoadError::BadHeader          => write!(f, "Not a brodg session"),
+            LoadError::BadLine(n, ref e)  => write!(f, "Line {}: {}", n, e),
+        }
+    }
+}
+
+impl From<io::Error> for LoadError {
+    fn from(e : io::Error) -> LoadError {
+        LoadError::Io(e)
+    }
+}
+
+fn write_entry<W : Write>(out : &mut W, entry : &Entry) -> io::Result<()> {
+    writeln!(out, "entry")?;
+    writeln!(out, "board {}", entry.board_num())?;
+    if let Some(d) = entry.declarer() {
+        writeln!(out, "declarer {}", d)?;
+    }
+    writeln!(out, "name {}", entry.name())?;
+    writeln!(out, "mode {}", entry.mode())?;
+    writeln!(out, "vulnerable {}", vulnerability_tag(entry.vulnerability()))?;
+    if let Some(c) = entry.contract() {
+        writeln!(out, "contract {}", c)?;
+    }
+    if let Some(h) = entry.honors() {
+        writeln!(out, "honors {}", h)?;
+    }
+    if let Some(r) = entry.result() {
+        writeln!(out, "result {}", r)?;
+    }
+    if let Some(r) = entry.room() {
+        writeln!(out, "room {}", r)?;
+    }
+    if let Some(d) = entry.deal() {
+        writeln!(out, "deal {}", d.to_pbn(Seat::North))?;
+    }
+    if let Some(p) = entry.play() {
+        let cards : Vec<String> =
+            p.cards().iter().map(|c| c.to_string()).collect();
+        writeln!(out, "play {}", cards.join(" "))?;
+        if let Some(t) = p.claimed() {
+            writeln!(out, "claim {}", t)?;
+        }
+    }
+    writeln!(out, "end")
+}
+
+// The history follows the entries, oldest edit first. Each edit is an
+// "edit" line followed by what it changed: "was" and "now" lines for a
+// player, or entry blocks for an entry. Entries in "edit change" count
+// from 1.
+fn write_history<W : Write>(out : &mut W, history : &History)
+    -> io::Result<()> {
+    for edit in history.edits() {
+        match *edit {
+            Edit::Player(seat, ref before, ref after) => {
+                writeln!(out, "edit player {}", seat)?;
+                writeln!(out, "was {}", before)?;
+                writeln!(out, "now {}", after)?;
+            },
+            Edit::Change(ix, ref before, ref after) => {
+                writeln!(out, "edit change {}", ix + 1)?;
+                write_entry(out, before)?;
+                write_entry(out, after)?;
+            },
+            Edit::Add(ref entry) => {
+                writeln!(out, "edit add")?;
+                write_entry(out, entry)?;
+            },
+        }
+    }
+    if history.undone() > 0 {
+        writeln!(out, "undone {}", history.undone())?;
+    }
+    Ok(())
+}
+
+pub fn write_session<W : Write>(out : &mut W, table : &Table,
+                                mode : ScoringMode, dealer : Seat,
+                                entries : &[Entry], history : &History)
+    -> io::Result<()> {
+    writeln!(out, "{}", HEADER)?;
+    writeln!(out, "mode {}", mode)?;
+    writeln!(out, "dealer {}", dealer)?;
+    for &seat in &SEATS {
+        writeln!(out, "player {} {}", seat, table.get_player(seat))?;
+    }
+    for entry in entries {
+        write_entry(out, entry)?;
+    }
+    write_history(out, history)
+}
+
+// Writes to a temporary file first, so that a crash halfway through never
+// leaves a truncated session behind.
+pub fn save_session(path : &Path, table : &Table, mode : ScoringMode,
+                    dealer : Seat, entries : &[Entry], history : &History)
+    -> io::Result<()> {
+    let temp = path.with_extension("tmp");
+    {
+        let mut out = BufWriter::new(File::create(&temp)?);
+        write_session(&mut out, table, mode, dealer, entries, history)?;
+        out.flush()?;
+    }
+    fs::rename(&temp, path)
+}
+
+// The fields of an entry block, applied once the whole block is read.
+#[derive(Default)]
+struct EntryFields {
+    board : Option<u32>,
+    declarer : Option<Seat>,
+    name : Option<String>,
+    mode : Option<ScoringMode>,
+    vulnerable : Option<(bool, bool)>,
+    contract : Option<String>,
+    honors : Option<String>,
+    result : Option<i32>,
+    room : Option<String>,
+    deal : Option<String>,
+    play : Option<String>,
+    claim : Option<u32>,
+}
+
+impl EntryFields {
+    fn build(self, table : &Table, mode : ScoringMode)
+        -> Result<Entry, String> {
+        let board = self.board.ok_or("Entry without a board")?;
+        let declarer = self.declarer.ok_or("Entry without a declarer")?;
+        let mut entry = Entry::with_mode(table, declarer, board,
+                                         self.mode.unwrap_or(mode));
+        if let Some(name) = self.name {
+            entry.set_name(name);
+        }
+        if let Some((ns, ew)) = self.vulnerable {
+            entry.set_vulnerability(ns, ew);
+        }
+        if let Some(c) = self.contract {
+            entry.set_contract(parse_input(&c)
+                               .map_err(|e| format!("{:?}", e))?);
+        }
+        if let Some(h) = self.honors {
+            let honors = parse_honors(&h).map_err(|e| format!("{:?}", e))?;
+            entry.set_honors(honors).map_err(|e| format!("{:?}", e))?;
+        }
+        if let Some(r) = self.result {
+            entry.record(r);
+        }
+        if let Some(r) = self.room {
+            entry.set_room(parse_room(&r));
+        }
+        if let Some(d) = self.deal {
+            entry.set_deal(Some(d.parse()?));
+        }
+        if let Some(p) = self.play {
+            for card in p.split_whitespace() {
+                entry.play_card(card.parse()?).map_err(|e| e.to_string())?;
+            }
+        }
+        if let Some(t) = self.claim {
+            entry.claim(t).map_err(|e| e.to_string())?;
+        }
+        Ok(entry)
+    }
+}
+
+// An edit that's still waiting for what it changed.
+enum PendingEdit {
+    Player(Seat, Option<String>),
+    // The entry before, once it's been read.
+    Change(usize, Option<Entry>),
+    Add,
+}
+
+fn parse_edit(value : &str) -> Result<PendingEdit, String> {
+    let mut parts = value.split(' ');
+    match (parts.next(), parts.next()) {
+        (Some("player"), Some(seat)) =>
+            Ok(PendingEdit::Player(seat.parse()?, None)),
+        (Some("change"), Some(n)) => match n.parse::<usize>() {
+            Ok(n) if n > 0 => Ok(PendingEdit::Change(n - 1, None)),
+            _              => Err(String::from("Invalid entry")),
+        },
+        (Some("add"), None) => Ok(PendingEdit::Add),
+        _ => Err(format!("Unknown edit: {}", value)),
+    }
+}
+
+// A finished entry block belongs to the edit being read, if there is one.
+fn add_entry(saved : &mut SavedSession, edit : &mut Option<PendingEdit>,
+             entry : Entry) -> Result<(), String> {
+    match edit.take() {
+        None => saved.entries.push(entry),
+        Some(PendingEdit::Add) => saved.history.push(Edit::Add(entry)),
+        Some(PendingEdit::Change(ix, None)) =>
+            *edit = Some(PendingEdit::Change(ix, Some(entry))),
+        Some(PendingEdit::Change(ix, Some(before))) =>
+            saved.history.push(Edit::Change(ix, before, entry)),
+        Some(PendingEdit::Player(..)) =>
+            return Err(String::from("Entry in a player edit")),
+    }
+    Ok(())
+}
+
+fn parse_history(saved : &mut SavedSession, edit : &mut Option<PendingEdit>,
+                 key : &str, value : &str) -> Result<(), String> {
+    match (key, edit.take()) {
+        ("edit", None) => *edit = Some(parse_edit(value)?),
+        ("was", Some(PendingEdit::Player(seat, None))) =>
+            *edit = Some(PendingEdit::Player(seat, Some(String::from(value)))),
+        ("now", Some(PendingEdit::Player(seat, Some(before)))) =>
+            saved.history.push(Edit::Player(seat, before,
+                                            String::from(value))),
+        ("undone", None) => {
+            let n : usize = value.parse().map_err(|_| "Invalid count")?;
+            for _ in 0..n {
+                saved.history.undo().ok_or("Too many edits undone")?;
+            }
+        },
+        (_, Some(_)) => return Err(String::from("Unfinished edit")),
+        (_, None)    => return Err(format!("Unknown key: {}", key)),
+    }
+    Ok(())
+}
+
+fn parse_line(saved : &mut SavedSession, current : &mut Option<EntryFields>,
+              edit : &mut Option<PendingEdit>, key : &str, value : &str)
+    -> Result<(), String> {
+    let fields = match *current {
+        Some(ref mut f) => f,
+        None => {
+            match key {
+                "mode"   => saved.mode = value.parse()?,
+                "dealer" => saved.dealer = value.parse()?,
+                "player" => {
+                    let mut parts = value.splitn(2, ' ');
+                    let seat : Seat = parts.next().unwrap_or("").parse()?;
+                    saved.table.set_player(seat, parts.next().unwrap_or(""));
+                },
+                "entry"  => *current = Some(EntryFields::default()),
+                _        => parse_history(saved, edit, key, value)?,
+            }
+            return Ok(());
+        },
+    };
+    match key {
+        // There's no board 0.
+        "board"      => fields.board = Some(
+            value.parse().ok().filter(|&b| b > 0).ok_or("Invalid board")?),
+        "declarer"   => fields.declarer = Some(value.parse()?),
+        "name"       => fields.name = Some(String::from(value)),
+        "mode"       => fields.mode = Some(value.parse()?),
+        "vulnerable" => fields.vulnerable = Some(
+            parse_vulnerability(value).ok_or("Invalid vulnerability")?),
+        "contract"   => fields.contract = Some(String::from(value)),
+        "honors"     => fields.honors = Some(String::from(value)),
+        "result"     => fields.result =
+            Some(value.parse().map_err(|_| "Invalid result")?),
+        "room"       => fields.room = Some(String::from(value)),
+        "deal"       => fields.deal = Some(String::from(value)),
+        "play"       => fields.play = Some(String::from(value)),
+        "claim"      => fields.claim =
+            Some(value.parse().map_err(|_| "Invalid claim")?),
+        "end"        => {
+            let entry = current.take().unwrap().build(&saved.table,
+                                                      saved.mode)?;
+            add_entry(saved, edit, entry)?;
+        },
+        _            => return Err(format!("Unknown key: {}", key)),
+    }
+    Ok(())
+}
+
+pub fn read_session<R : BufRead>(input : R) -> Result<SavedSession, LoadError> {
+    let mut lines = input.lines();
+    match lines.next() {
+        Some(line) => if line? != HEADER { return Err(LoadError::BadHeader); },
+        None       => return Err(LoadError::BadHeader),
+    }
+    let mut saved = SavedSession {
+        table : Table::new(),
+        mode : ScoringMode::Duplicate,
+        dealer : Seat::North,
+        entries : Vec::new(),
+        history : History::new(),
+    };
+    let mut current = None;
+    let mut edit = None;
+    // A file cut short is blamed on its last line.
+    let mut last = 1;
+    for (n, line) in lines.enumerate() {
+        let line = line?;
+        last = n + 2;
+        if line.is_empty() {
+            continue;
+        }
+        let mut parts = line.splitn(2, ' ');
+        let key = parts.next().unwrap_or("");
+        let value = parts.next().unwrap_or("");
+        parse_line(&mut saved, &mut current, &mut edit, key, value)
+            .map_err(|e| LoadError::BadLine(n + 2, e))?;
+    }
+    if current.is_some() {
+        return Err(LoadError::BadLine(last,
+                                      String::from("Unfinished entry")));
+    }
+    if edit.is_some() {
+        return Err(LoadError::BadLine(last,
+                                      String::from("Unfinished edit")));
+    }
+    Ok(saved)
+}
+
+pub fn load_session(path : &Path) -> Result<SavedSession, LoadError> {
+    read_session(BufReader::new(File::open(path)?))
+}
+
+#[test]
+fn test_round_trip() {
+    let mut table = Table::new();
+    table.set_player(Seat::East, "Anne Marie");
+    let mut first = Entry::with_mode(&table, Seat::East, 2,
+                                     ScoringMode::Chicago);
+    first.set_contract(parse_input("4hx").unwrap());
+    first.set_honors(parse_honors("-4").unwrap()).unwrap();
+    first.record(-1);
+    let mut second = Entry::new(&table, Seat::South, 3);
+    second.set_vulnerability(true, true);
+    second.set_room(parse_room("b"));
+    second.set_deal(Some("W:- - AKQJ.T98.765.432 -".parse().unwrap()));
+
+    let mut out = Vec::new();
+    write_session(&mut out, &table, ScoringMode::Chicago, Seat::West,
+                  &[first, second], &History::new()).unwrap();
+    let saved = read_session(&out[..]).unwrap();
+    assert!(saved.mode == ScoringMode::Chicago);
+    assert!(saved.dealer == Seat::West);
+    assert!(saved.table.get_player(Seat::East) == "Anne Marie");
+    assert!(saved.entries.len() == 2);
+    let first = &saved.entries[0];
+    assert!(first.name() == "Anne Marie");
+    assert!(first.contract().unwrap().to_string() == "4HX");
+    assert!(first.honors().unwrap().to_string() == "-4");
+    assert!(first.value() == Some(-300));
+    let second = &saved.entries[1];
+    assert!(second.contract().is_none() && second.result().is_none());
+    assert!(second.vulnerability() == (true, true));
+    assert!(second.room().unwrap().to_string() == "B");
+    assert!(second.deal().unwrap().hand(Seat::East).len() == 13);
+    assert!(first.deal().is_none());
+}
+
+#[test]
+fn test_play_round_trip() {
+    let table = Table::new();
+    let mut entry = Entry::new(&table, Seat::North, 1);
+    entry.set_contract(parse_input("4s").unwrap());
+    entry.set_deal(Some("N:AKQJ.T98.765.432 T987.654.32.AKQJ \
+                         65432.AKQ.J4.T98 .J732.AKQT98.765".parse().unwrap()));
+    for card in &["CA", "C8", "C5", "C2", "CK"] {
+        entry.play_card(card.parse().unwrap()).unwrap();
+    }
+    entry.claim(10).unwrap();
+
+    let mut out = Vec::new();
+    write_session(&mut out, &table, ScoringMode::Duplicate, Seat::North,
+                  &[entry], &History::new()).unwrap();
+    let saved = read_session(&out[..]).unwrap();
+    let play = saved.entries[0].play().unwrap();
+    assert!(play.cards().len() == 5);
+    assert!(play.claimed() == Some(10));
+    assert!(saved.entries[0].result() == Some(0));
+}
+
+#[test]
+fn test_bad_input() {
+    assert!(matches!(read_session(&b"hello\n"[..]),
+                     Err(LoadError::BadHeader)));
+    let input = format!("{}\nentry\nboard 1\ndeclarer Q\nend\n", HEADER);
+    assert!(matches!(read_session(input.as_bytes()),
+                     Err(LoadError::BadLine(4, _))));
+    let input = format!("{}\nentry\nboard 0\ndeclarer N\nend\n", HEADER);
+    assert!(matches!(read_session(input.as_bytes()),
+                     Err(LoadError::BadLine(3, _))));
+    let input = format!("{}\nentry\nboard 1\n", HEADER);
+    assert!(matches!(read_session(input.as_bytes()),
+                     Err(LoadError::BadLine(3, _))));
+}
+
+#[test]
+fn test_history_round_trip() {
+    let table = Table::new();
+    let added = Entry::new(&table, Seat::South, 1);
+    let mut changed = added.clone();
+    changed.set_contract(parse_input("3nt").unwrap());
+    changed.record(1);
+    let mut history = History::new();
+    history.push(Edit::Add(added.clone()));
+    history.push(Edit::Change(0, added.clone(), changed.clone()));
+    history.push(Edit::Player(Seat::West, String::from("West"),
+                              String::from("")));
+    history.undo();
+
+    let mut out = Vec::new();
+    write_session(&mut out, &table, ScoringMode::Duplicate, Seat::North,
+                  &[changed.clone()], &history).unwrap();
+    let mut saved = read_session(&out[..]).unwrap();
+    assert!(saved.entries == vec![changed.clone()]);
+    assert!(saved.history == history);
+    assert!(matches!(saved.history.redo(),
+                     Some(&Edit::Player(Seat::West, _, ref n))
+                     if n.is_empty()));
+
+    let input = format!("{}\nedit change 1\nentry\nboard 1\ndeclarer N\nend\n",
+                        HEADER);
+    assert!(matches!(read_session(input.as_bytes()),
+                     Err(LoadError::BadLine(6, _))));
+    let input = format!("{}\nedit add\nundone 1\n", HEADER);
+    assert!(matches!(read_session(input.as_bytes()),
+                     Err(LoadError::BadLine(3, _))));
+}
@@ -1,4 +1,141 @@
-use contract::{Contract, ContractNumber, ContractSuit, ContractDoubled};
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use board;
+use chicago;
+use contract::{Contract, ContractNumber, ContractSuit, ContractDoubled, Seat};
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ScoringMode {
+    Duplicate, Rubber, Chicago
+}
+
+impl ScoringMode {
+    pub fn next(self) -> ScoringMode {
+        match self {
+            ScoringMode::Duplicate => ScoringMode::Rubber,
+            ScoringMode::Rubber    => ScoringMode::Chicago,
+            ScoringMode::Chicago   => ScoringMode::Duplicate,
+        }
+    }
+
+    // The deal passes clockwise in every mode.
+    pub fn dealer(self, board_num : u32) -> Seat {
+        board::dealer(board_num)
+    }
+
+    // NS and EW vulnerability. Rubber vulnerability depends on the games
+    // already won, so it has to come from a `rubber::RubberScorer` instead.
+    pub fn vulnerability(self, board_num : u32) -> (bool, bool) {
+        match self {
+            ScoringMode::Duplicate => board::vulnerability(board_num),
+            ScoringMode::Rubber    => (false, false),
+            ScoringMode::Chicago   => chicago::vulnerability(board_num),
+        }
+    }
+
+    // Honors only count outside of duplicate. They should already have been
+    // checked against the contract with `Honors::check`.
+    pub fn score(self, contract : &Contract, is_vulnerable : bool,
+                 board_num : u32, honors : Option<Honors>) -> Score {
+        match (self, honors) {
+            (ScoringMode::Duplicate, _) =>
+                Score::from_contract(contract, is_vulnerable),
+            (ScoringMode::Rubber, Some(h)) =>
+                Score::without_making_bonus(contract, is_vulnerable)
+                    .with_honors(h),
+            (ScoringMode::Rubber, None) =>
+                Score::without_making_bonus(contract, is_vulnerable),
+            (ScoringMode::Chicago, _) => {
+                let score = Score::with_part_score_bonus(
+                    contract, is_vulnerable,
+                    chicago::part_score_bonus(board_num));
+                match honors {
+                    Some(h) => score.with_honors(h),
+                    None    => score,
+                }
+            },
+        }
+    }
+}
+
+impl fmt::Display for ScoringMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}",
+               match *self {
+                   ScoringMode::Duplicate => "Duplicate",
+                   ScoringMode::Rubber    => "Rubber",
+                   ScoringMode::Chicago   => "Chicago",
+               })
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum HonorsKind {
+    FourTrump, FiveTrump, FourAces
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum HonorsError {
+    NoContract,
+    NeedsTrumpSuit,
+    NeedsNoTrump,
+}
+
+impl fmt::Display for HonorsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match *self {
+            HonorsError::NoContract     => "Honors need a contract",
+            HonorsError::NeedsTrumpSuit => "Honors need a trump suit",
+            HonorsError::NeedsNoTrump   => "Four aces need notrump",
+        })
+    }
+}
+
+// Honors held in one hand, by either the declaring side or the defenders.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Honors {
+    pub kind : HonorsKind,
+    pub declarer_side : bool,
+}
+
+impl Honors {
+    pub fn new(kind : HonorsKind, declarer_side : bool) -> Honors {
+        Honors {
+            kind,
+            declarer_side,
+        }
+    }
+
+    // Trump honors need a trump suit, and four aces only count at notrump.
+    pub fn check(&self, suit : ContractSuit) -> Result<(), HonorsError> {
+        match (self.kind, suit) {
+            (HonorsKind::FourAces, ContractSuit::NoTrump) => Ok(()),
+            (HonorsKind::FourAces, _) => Err(HonorsError::NeedsNoTrump),
+            (_, ContractSuit::NoTrump) => Err(HonorsError::NeedsTrumpSuit),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn value(&self) -> i32 {
+        match self.kind {
+            HonorsKind::FourTrump => 100,
+            HonorsKind::FiveTrump | HonorsKind::FourAces => 150,
+        }
+    }
+}
+
+impl fmt::Display for Honors {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}",
+               if self.declarer_side { "" } else { "-" },
+               match self.kind {
+                   HonorsKind::FourTrump => "4",
+                   HonorsKind::FiveTrump => "5",
+                   HonorsKind::FourAces  => "A",
+               })
+    }
+}
 
 pub struct Score {
     pub first_trick_value : i32,
@@ -11,6 +148,9 @@ pub struct Score {
     pub setting : i32,
     pub next_undertricks : i32,
     pub rest_undertricks : i32,
+    // Scored whether or not the contract makes, and negative when the
+    // defenders hold them.
+    pub honors : i32,
 }
 
 impl Score {
@@ -24,14 +164,42 @@ impl Score {
     }
 
     pub fn score_result(&self, margin : i32) -> i32 {
-        if margin < 0 {
+        let score = if margin < 0 {
             -self.score_undertricks(-margin)
         } else {
             self.contract_value + self.overtricks * margin
-        }
+        };
+        score + self.honors
+    }
+
+    // What the tricks bid are worth, before any bonus.
+    pub fn trick_score(&self) -> i32 {
+        self.contract_value - self.insult - self.making_bonus - self.slam_bonus
+    }
+
+    pub fn with_honors(mut self, honors : Honors) -> Score {
+        self.honors =
+            if honors.declarer_side { honors.value() } else { -honors.value() };
+        self
     }
 
     pub fn from_contract(contract : &Contract, is_vulnerable : bool) -> Score {
+        Score::with_part_score_bonus(contract, is_vulnerable, 50)
+    }
+
+    // Rubber bridge scores games and part-scores as the rubber goes, not
+    // with each deal, so a made contract only gets its tricks, insult and
+    // slam bonus.
+    pub fn without_making_bonus(contract : &Contract, is_vulnerable : bool)
+        -> Score {
+        let mut score = Score::from_contract(contract, is_vulnerable);
+        score.contract_value -= score.making_bonus;
+        score.making_bonus = 0;
+        score
+    }
+
+    pub fn with_part_score_bonus(contract : &Contract, is_vulnerable : bool,
+                                 normal_bonus : i32) -> Score {
         let trick_value = match contract.suit {
             ContractSuit::Clubs | ContractSuit::Diamonds => 20,
             _                                            => 30,
@@ -49,7 +217,6 @@ impl Score {
         let contract_value = doubling_bonus *
             (first_trick + (contract.number.into_i32() - 1) * trick_value);
 
-        let normal_bonus = 50;
         let game_bonus = if is_vulnerable { 500 } else { 300 };
 
         let making_bonus =
@@ -93,18 +260,24 @@ impl Score {
         Score {
             first_trick_value: first_trick * doubling_bonus,
             trick_value : trick_value * doubling_bonus,
-            making_bonus : making_bonus,
-            insult : insult,
-            slam_bonus : slam_bonus,
+            making_bonus,
+            insult,
+            slam_bonus,
             contract_value : making_value,
             overtricks : overtrick_value,
             setting : penalties.0,
             next_undertricks : penalties.1,
             rest_undertricks : penalties.2,
+            honors : 0,
         }
     }
 }
 
+// From taking no tricks to taking all thirteen.
+pub fn possible_results(number : ContractNumber) -> RangeInclusive<i32> {
+    -6 - number.into_i32()..=7 - number.into_i32()
+}
+
 pub fn score_game(contract : &Contract, margin : i32, is_vulnerable : bool)
     -> i32 {
     Score::from_contract(contract, is_vulnerable).score_result(margin)
@@ -0,0 +1,240 @@
+use std::collections::BTreeMap;
+
+use data::Entry;
+use movement::Movement;
+
+pub type PairId = u32;
+
+// One table's result on a board.
+pub struct TableResult {
+    pub ns_pair : PairId,
+    pub ew_pair : PairId,
+    pub entry : Entry,
+}
+
+pub struct BoardRow {
+    pub ns_pair : PairId,
+    pub ew_pair : PairId,
+    pub ns_score : i32,
+    pub ns_matchpoints : f64,
+    pub ew_matchpoints : f64,
+}
+
+// Matchpoints for every result on one board, on a common top of 2 per pair
+// beaten across the whole session.
+pub struct BoardMatchpoints {
+    pub board_num : u32,
+    pub top : f64,
+    pub rows : Vec<BoardRow>,
+}
+
+pub struct PairStanding {
+    pub pair : PairId,
+    pub matchpoints : f64,
+    pub possible : f64,
+    pub percentage : f64,
+}
+
+// Results from every table in a pairs game, grouped by board.
+pub struct Session {
+    results_ : Vec<TableResult>,
+}
+
+// Raw matchpoints for `score`: 2 for each other score beaten, 1 per tie.
+fn raw_matchpoints(score : i32, scores : &[i32]) -> f64 {
+    let mut mp = -1;
+    for &other in scores {
+        if score > other {
+            mp += 2;
+        } else if score == other {
+            mp += 1;
+        }
+    }
+    mp as f64
+}
+
+// Neuberg's formula, to put a board played `played` times on the same top
+// as one played `expected` times.
+fn neuberg(mp : f64, played : usize, expected : usize) -> f64 {
+    (mp + 1.0) * expected as f64 / played as f64 - 1.0
+}
+
+impl Session {
+    pub fn new() -> Session {
+        Session {
+            results_ : Vec::new(),
+        }
+    }
+
+    // Entries without a score yet are kept, but don't count toward anything.
+    pub fn add(&mut self, ns_pair : PairId, ew_pair : PairId, entry : Entry) {
+        self.results_.push(TableResult {
+            ns_pair,
+            ew_pair,
+            entry,
+        });
+    }
+
+    // Adds a result from `table` for the pairs the movement put there to
+    // play the entry's board. False if it never sends that board there.
+    pub fn add_at_table(&mut self, movement : &Movement, table : u32,
+                        entry : Entry) -> bool {
+        let (ns_pair, ew_pair) = match movement.find(table,
+                                                     entry.board_num()) {
+            Some(a) => (a.ns_pair, a.ew_pair),
+            None    => return false,
+        };
+        self.add(ns_pair, ew_pair, entry);
+        true
+    }
+
+    pub fn results(&self) -> &[TableResult] {
+        &self.results_
+    }
+
+    // Scored results with their NS score, grouped by board.
+    pub fn by_board(&self) -> BTreeMap<u32, Vec<(&TableResult, i32)>> {
+        let mut boards = BTreeMap::new();
+        for result in &self.results_ {
+            if let Some(score) = result.entry.ns_score() {
+                boards.entry(result.entry.board_num())
+                    .or_insert_with(Vec::new)
+                    .push((result, score));
+            }
+        }
+        boards
+    }
+
+    pub fn matchpoints(&self) -> Vec<BoardMatchpoints> {
+        let boards = self.by_board();
+        let expected = boards.values().map(Vec::len).max().unwrap_or(0);
+        let top = 2.0 * (expected as f64 - 1.0);
+        boards.iter().map(|(&board_num, results)| {
+            let scores : Vec<i32> = results.iter().map(|r| r.1).collect();
+            let rows = results.iter().map(|&(result, score)| {
+                let ns_mp = neuberg(raw_matchpoints(score, &scores),
+                                    scores.len(), expected);
+                BoardRow {
+                    ns_pair : result.ns_pair,
+                    ew_pair : result.ew_pair,
+                    ns_score : score,
+                    ns_matchpoints : ns_mp,
+                    ew_matchpoints : top - ns_mp,
+                }
+            }).collect();
+            BoardMatchpoints {
+                board_num,
+                top,
+                rows,
+            }
+        }).collect()
+    }
+
+    // Every pair's matchpoint total as a percentage of the tops available
+    // on the boards they played, best first.
+    pub fn ranking(&self) -> Vec<PairStanding> {
+        let mut totals : BTreeMap<PairId, (f64, f64)> = BTreeMap::new();
+        for board in self.matchpoints() {
+            for row in &board.rows {
+                for &(pair, mp) in &[(row.ns_pair, row.ns_matchpoints),
+                                     (row.ew_pair, row.ew_matchpoints)] {
+                    let total = totals.entry(pair).or_insert((0.0, 0.0));
+                    total.0 += mp;
+                    total.1 += board.top;
+                }
+            }
+        }
+        let mut standings : Vec<PairStanding> =
+            totals.into_iter().map(|(pair, (matchpoints, possible))| {
+                PairStanding {
+                    pair,
+                    matchpoints,
+                    possible,
+                    percentage : if possible > 0.0 {
+                        100.0 * matchpoints / possible
+                    } else {
+                        50.0
+                    },
+                }
+            }).collect();
+        standings.sort_by(|a, b| b.percentage.partial_cmp(&a.percentage)
+                          .unwrap().then(a.pair.cmp(&b.pair)));
+        standings
+    }
+}
+
+impl Default for Session {
+    fn default() -> Session {
+        Session::new()
+    }
+}
+
+#[cfg(test)]
+use contract::Seat;
+#[cfg(test)]
+use movement::{mitchell, EvenTables};
+#[cfg(test)]
+use data::Table;
+#[cfg(test)]
+use parse::parse_contract;
+
+#[cfg(test)]
+fn result(board : u32, declarer : Seat, contract : &str, margin : i32)
+    -> Entry {
+    let mut entry = Entry::new(&Table::new(), declarer, board);
+    entry.set_contract(parse_contract(contract).unwrap());
+    entry.record(margin);
+    entry
+}
+
+#[test]
+fn test_board_matchpoints() {
+    let mut session = Session::new();
+    session.add(1, 11, result(1, Seat::North, "4S", 0));
+    session.add(2, 12, result(1, Seat::North, "4S", 1));
+    session.add(3, 13, result(1, Seat::South, "4S", 0));
+    session.add(4, 14, result(1, Seat::East, "5DX", -2));
+    let boards = session.matchpoints();
+    assert!(boards.len() == 1);
+    let rows = &boards[0].rows;
+    assert!(boards[0].top == 6.0);
+    assert!(rows[0].ns_score == 420 && rows[0].ns_matchpoints == 3.0);
+    assert!(rows[1].ns_matchpoints == 6.0);
+    assert!(rows[2].ew_matchpoints == 3.0);
+    assert!(rows[3].ns_score == 300 && rows[3].ns_matchpoints == 0.0);
+}
+
+#[test]
+fn test_neuberg_and_ranking() {
+    let mut session = Session::new();
+    session.add(1, 11, result(1, Seat::North, "3NT", 0));
+    session.add(2, 12, result(1, Seat::North, "3NT", -1));
+    session.add(3, 13, result(1, Seat::North, "3NT", 1));
+    session.add(1, 12, result(2, Seat::North, "2H", 0));
+    session.add(2, 11, result(2, Seat::North, "2H", -1));
+    let boards = session.matchpoints();
+    // Two results on a board where the top is 4: beating the other one is
+    // worth (2 + 1) * 3 / 2 - 1.
+    assert!(boards[1].rows[0].ns_matchpoints == 3.5);
+    assert!(boards[1].rows[1].ns_matchpoints == 0.5);
+
+    let ranking = session.ranking();
+    assert!(ranking[0].pair == 3 && ranking[0].percentage == 100.0);
+    assert!(ranking.iter().all(|s| s.possible > 0.0));
+    let pair_one = ranking.iter().find(|s| s.pair == 1).unwrap();
+    assert!(pair_one.matchpoints == 2.0 + 3.5);
+    assert!(pair_one.possible == 8.0);
+}
+
+#[test]
+fn test_movement_pairs() {
+    let movement = mitchell(3, 2, EvenTables::Skip).unwrap();
+    let mut session = Session::new();
+    // Table 2 has boards 5 and 6 in the second round, against EW 4.
+    let entry = result(5, Seat::North, "2S", 0);
+    assert!(session.add_at_table(&movement, 2, entry));
+    let entry = result(7, Seat::North, "2S", 0);
+    assert!(!session.add_at_table(&movement, 2, entry));
+    let added = &session.results()[0];
+    assert!(added.ns_pair == 2 && added.ew_pair == 4);
+}
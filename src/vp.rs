@@ -0,0 +1,129 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::Add;
+
+use imp::MatchResult;
+
+pub type TeamId = u32;
+
+// Victory points are kept in hundredths so that totals add up exactly.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Default)]
+pub struct Vp(pub i32);
+
+impl Vp {
+    pub fn hundredths(self) -> i32 {
+        self.0
+    }
+}
+
+impl Add for Vp {
+    type Output = Vp;
+    fn add(self, other : Vp) -> Vp {
+        Vp(self.0 + other.0)
+    }
+}
+
+impl fmt::Display for Vp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{:02}", self.0 / 100, self.0 % 100)
+    }
+}
+
+const MAX_VP : i32 = 2000;
+
+// The WBF continuous 20-0 scale: the winner of a margin of `imps` over
+// `boards` boards gets 10 + 10 * (1 - t^(3M/B)) / (1 - t^3), where t is the
+// golden ratio conjugate and B = 15 * sqrt(boards) is the blitz margin.
+// Returns the VPs for the side with the margin, then for its opponents.
+pub fn victory_points(imps : i32, boards : u32) -> (Vp, Vp) {
+    let margin = imps.abs() as f64;
+    let blitz = 15.0 * (boards as f64).sqrt();
+    let winner = if margin >= blitz {
+        MAX_VP
+    } else {
+        let tau : f64 = (5.0f64.sqrt() - 1.0) / 2.0;
+        let vp = 10.0 + 10.0 * (1.0 - tau.powf(3.0 * margin / blitz)) /
+            (1.0 - tau.powi(3));
+        (vp * 100.0).round() as i32
+    };
+    if imps < 0 {
+        (Vp(MAX_VP - winner), Vp(winner))
+    } else {
+        (Vp(winner), Vp(MAX_VP - winner))
+    }
+}
+
+pub struct Standing {
+    pub team : TeamId,
+    pub vps : Vp,
+    pub matches : u32,
+}
+
+// VP totals over the rounds of a Swiss event.
+pub struct Standings {
+    totals_ : BTreeMap<TeamId, (Vp, u32)>,
+}
+
+impl Standings {
+    pub fn new() -> Standings {
+        Standings {
+            totals_ : BTreeMap::new(),
+        }
+    }
+
+    // `imps` is the margin for `home`.
+    pub fn add_match(&mut self, home : TeamId, away : TeamId, imps : i32,
+                     boards : u32) {
+        let (home_vps, away_vps) = victory_points(imps, boards);
+        for &(team, vps) in &[(home, home_vps), (away, away_vps)] {
+            let total = self.totals_.entry(team).or_insert((Vp(0), 0));
+            total.0 = total.0 + vps;
+            total.1 += 1;
+        }
+    }
+
+    // Team A of the match result is the home team.
+    pub fn add_result(&mut self, home : TeamId, away : TeamId,
+                      result : &MatchResult) {
+        self.add_match(home, away, result.net(), result.boards.len() as u32);
+    }
+
+    pub fn table(&self) -> Vec<Standing> {
+        let mut table : Vec<Standing> =
+            self.totals_.iter().map(|(&team, &(vps, matches))| Standing {
+                team,
+                vps,
+                matches,
+            }).collect();
+        table.sort_by(|a, b| b.vps.cmp(&a.vps).then(a.team.cmp(&b.team)));
+        table
+    }
+}
+
+impl Default for Standings {
+    fn default() -> Standings {
+        Standings::new()
+    }
+}
+
+#[test]
+fn test_wbf_scale() {
+    assert!(victory_points(0, 8) == (Vp(1000), Vp(1000)));
+    assert!(victory_points(1, 8) == (Vp(1044), Vp(956)));
+    assert!(victory_points(1, 16) == (Vp(1031), Vp(969)));
+    assert!(victory_points(-10, 12).1.to_string() == "13.18");
+    assert!(victory_points(43, 8) == (Vp(2000), Vp(0)));
+}
+
+#[test]
+fn test_standings() {
+    let mut standings = Standings::new();
+    standings.add_match(1, 2, 0, 8);
+    standings.add_match(1, 3, -50, 8);
+    standings.add_match(2, 3, 1, 8);
+    let table = standings.table();
+    assert!(table[0].team == 3 && table[0].vps == Vp(2956));
+    assert!(table[1].team == 2 && table[1].vps == Vp(2044));
+    assert!(table[2].team == 1 && table[2].vps.to_string() == "10.00");
+    assert!(table.iter().all(|s| s.matches == 2));
+}